@@ -0,0 +1,5 @@
+ALTER TABLE users ADD COLUMN next_bundle INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE users ADD COLUMN next_accessory_store INTEGER NOT NULL DEFAULT 0;
+
+DELETE FROM version;
+INSERT INTO version VALUES ('2');
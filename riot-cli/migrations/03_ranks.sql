@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS ranks (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER UNIQUE NOT NULL,
+    match_id TEXT NOT NULL,
+    competitive_tier INTEGER NOT NULL,
+    ranked_rating INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+
+    FOREIGN KEY (user_id) REFERENCES users (id)
+);
+
+DELETE FROM version;
+INSERT INTO version VALUES ('3');
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS player_names (
+    puuid TEXT PRIMARY KEY,
+    game_name TEXT NOT NULL,
+    tag_line TEXT NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+DELETE FROM version;
+INSERT INTO version VALUES ('4');
@@ -0,0 +1,4 @@
+ALTER TABLE users ADD COLUMN entitlements_expires_at INTEGER NOT NULL DEFAULT 0;
+
+DELETE FROM version;
+INSERT INTO version VALUES ('5');
@@ -0,0 +1,4 @@
+ALTER TABLE sessions ADD COLUMN cookie_expiry_alerted INTEGER;
+
+DELETE FROM version;
+INSERT INTO version VALUES ('6');
@@ -0,0 +1,5 @@
+ALTER TABLE users ADD COLUMN disabled INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE users ADD COLUMN alias TEXT;
+
+DELETE FROM version;
+INSERT INTO version VALUES ('8');
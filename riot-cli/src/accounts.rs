@@ -0,0 +1,157 @@
+use chrono::Utc;
+
+use crate::{datastore::Datastore, login::confirm_default_no, models::User, AccountCommands};
+
+pub fn handle_account_command(db: &Datastore, command: &AccountCommands) {
+    match command {
+        AccountCommands::List => list(db),
+        AccountCommands::Remove { account, yes } => remove(db, account, *yes),
+        AccountCommands::Disable { account } => set_disabled(db, account, true),
+        AccountCommands::Enable { account } => set_disabled(db, account, false),
+        AccountCommands::Alias { account, alias } => set_alias(db, account, alias.as_deref()),
+    }
+}
+
+pub fn list(db: &Datastore) {
+    let users = db.get_all_users().expect("users");
+    if users.is_empty() {
+        println!("No accounts, add one with `riot-cli val-store add`");
+        return;
+    }
+
+    for user in users {
+        let mut name = format!("{} {}", user.id, display_name(&user));
+        if user.disabled {
+            name.push_str(" [disabled]");
+        }
+        println!("{name}");
+
+        let session = match &user.session {
+            None => "not logged in".to_string(),
+            Some(_) if user.needs_reauth => "login required".to_string(),
+            Some(session) if session.expires_at < Utc::now() => {
+                "expired, refreshed on next use".to_string()
+            }
+            Some(session) => format!("active until {}", session.expires_at),
+        };
+        println!("  region            {}", user.region);
+        println!("  session           {session}");
+        println!("  next store        {}", user.next_store);
+        println!("  next night market {}", user.next_nightmarket);
+    }
+}
+
+pub fn remove(db: &Datastore, account: &str, yes: bool) {
+    let Some(user) = find_account(db, account) else {
+        return;
+    };
+    if !yes
+        && !confirm_default_no(&format!(
+            "Remove {} and its session? [y/N] ",
+            display_name(&user)
+        ))
+    {
+        println!("Kept account {}", display_name(&user));
+        return;
+    }
+
+    db.remove_user(&user.id).expect("user should be removed");
+    println!("Removed account {}", display_name(&user));
+}
+
+pub fn set_disabled(db: &Datastore, account: &str, disabled: bool) {
+    let Some(user) = find_account(db, account) else {
+        return;
+    };
+
+    db.set_user_disabled(&user.id, disabled)
+        .expect("user should be updated");
+    if disabled {
+        println!("Disabled account {}", display_name(&user));
+    } else {
+        println!("Enabled account {}", display_name(&user));
+    }
+}
+
+pub fn set_alias(db: &Datastore, account: &str, alias: Option<&str>) {
+    let Some(user) = find_account(db, account) else {
+        return;
+    };
+    if let Some(alias) = alias {
+        if let Err(reason) = check_alias(db, &user, alias) {
+            eprintln!("Cannot use {alias} as an alias: {reason}");
+            return;
+        }
+    }
+
+    db.set_user_alias(&user.id, alias)
+        .expect("user should be updated");
+    match alias {
+        Some(alias) => println!("{}#{} is now {alias}", user.game_name, user.tag_line),
+        None => println!("Cleared alias of {}#{}", user.game_name, user.tag_line),
+    }
+}
+
+/// `Name#Tag (alias)`
+fn display_name(user: &User) -> String {
+    match &user.alias {
+        Some(alias) => format!("{}#{} ({alias})", user.game_name, user.tag_line),
+        None => format!("{}#{}", user.game_name, user.tag_line),
+    }
+}
+
+/// An alias must not be a number, which would read as an id, nor name
+/// another account in any way `find_account` looks them up.
+fn check_alias(db: &Datastore, user: &User, alias: &str) -> Result<(), String> {
+    if alias.trim().is_empty() {
+        return Err("it is empty".to_string());
+    }
+    if alias.parse::<i64>().is_ok() {
+        return Err("numbers are account ids".to_string());
+    }
+
+    let users = db.get_all_users().expect("users");
+    match users
+        .iter()
+        .find(|other| other.id != user.id && matches_account(other, alias))
+    {
+        Some(other) => Err(format!("it already names {}", display_name(other))),
+        None => Ok(()),
+    }
+}
+
+fn matches_account(user: &User, account: &str) -> bool {
+    format!("{}#{}", user.game_name, user.tag_line).eq_ignore_ascii_case(account)
+        || user
+            .alias
+            .as_deref()
+            .is_some_and(|alias| alias.eq_ignore_ascii_case(account))
+        || user.puuid == account
+        || user.id.to_string() == account
+}
+
+/// Finds an account by `Name#Tag`, alias, puuid or id, disabled ones included.
+/// Nothing is returned when several accounts match.
+fn find_account(db: &Datastore, account: &str) -> Option<User> {
+    let users = db.get_all_users().expect("users");
+    let mut found: Vec<_> = users
+        .into_iter()
+        .filter(|user| matches_account(user, account))
+        .collect();
+
+    match found.len() {
+        0 => {
+            eprintln!("No account matches {account}");
+            None
+        }
+        1 => found.pop(),
+        _ => {
+            let names: Vec<_> = found
+                .iter()
+                .map(|user| format!("{} {}", user.id, display_name(user)))
+                .collect();
+            eprintln!("{account} matches several accounts: {}", names.join(", "));
+            None
+        }
+    }
+}
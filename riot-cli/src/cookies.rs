@@ -0,0 +1,335 @@
+use chrono::Utc;
+use cookie_store::{CookieStore, RawCookie};
+use serde::Deserialize;
+use url::Url;
+
+const AUTH_URL: &str = "https://auth.riotgames.com/";
+
+/// Cookie as exported by a browser.
+#[derive(Debug)]
+struct ExportedCookie {
+    domain: String,
+    host_only: bool,
+    path: String,
+    secure: bool,
+    /// Unix timestamp in seconds, `None` for session cookies.
+    expires: Option<i64>,
+    name: String,
+    value: String,
+}
+
+/// Cookie-Editor style entry, also used by Playwright storage states.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonCookie {
+    domain: String,
+    name: String,
+    value: String,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    secure: bool,
+    #[serde(default)]
+    host_only: bool,
+    expiration_date: Option<f64>,
+    expires: Option<f64>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonExport {
+    Cookies(Vec<JsonCookie>),
+    StorageState { cookies: Vec<JsonCookie> },
+}
+
+/// Builds the auth.riotgames.com cookie jar out of a cookies export.
+///
+/// Accepts a Netscape `cookies.txt`, a JSON export or a plain
+/// `name=value; name=value` header, cookies of other sites are dropped.
+pub fn import_auth_cookies(contents: &str) -> Result<CookieStore, String> {
+    let contents = contents.trim();
+    let store = if contents.starts_with('[') || contents.starts_with('{') {
+        auth_cookie_store(parse_json(contents)?)
+    } else if contents.lines().any(|line| line.contains('\t')) {
+        auth_cookie_store(parse_netscape(contents))
+    } else {
+        cookie_store_from_header(contents)
+    };
+
+    let url = Url::parse(AUTH_URL).expect("auth url should be valid");
+    if store.get_request_values(&url).next().is_none() {
+        return Err("no auth.riotgames.com cookies found".to_string());
+    }
+
+    Ok(store)
+}
+
+/// Jar holding the unexpired exported cookies sent to auth.riotgames.com.
+fn auth_cookie_store(cookies: Vec<ExportedCookie>) -> CookieStore {
+    let url = Url::parse(AUTH_URL).expect("auth url should be valid");
+    let now = Utc::now().timestamp();
+    let mut store = CookieStore::default();
+    for cookie in cookies {
+        if cookie.expires.is_some_and(|expires| expires <= now) {
+            continue;
+        }
+        // the store rejects cookies whose domain does not match auth.riotgames.com
+        if let Some(raw) = cookie.to_raw(now) {
+            let _ = store.insert_raw(&raw, &url);
+        }
+    }
+
+    store
+}
+
+/// Jar holding the cookies of a `name=value; name=value` header as
+/// auth.riotgames.com session cookies.
+pub fn cookie_store_from_header(header: &str) -> CookieStore {
+    let url = Url::parse(AUTH_URL).expect("auth url should be valid");
+    let mut store = CookieStore::default();
+    for pair in header
+        .split(';')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+    {
+        if let Ok(raw) = RawCookie::parse(format!("{pair}; Path=/")) {
+            let _ = store.insert_raw(&raw, &url);
+        }
+    }
+
+    store
+}
+
+/// Serializes the unexpired cookies of the jar, session cookies included
+/// since the jar outlives the process.
+pub fn save_cookie_store(store: &CookieStore) -> String {
+    // same format as `cookie_store::serde::json::save`, which drops session cookies
+    let cookies: Vec<_> = store.iter_unexpired().collect();
+    serde_json::to_string_pretty(&cookies).expect("cookies should serialize")
+}
+
+/// Loads a jar saved by `save_cookie_store`, sessions saved before the jar
+/// was persisted hold a plain cookie header instead. A jar that cannot be
+/// read is reported and replaced by an empty one.
+pub fn load_cookie_store(saved: &str) -> CookieStore {
+    if !saved.trim_start().starts_with('[') {
+        return cookie_store_from_header(saved);
+    }
+
+    cookie_store::serde::json::load(saved.as_bytes()).unwrap_or_else(|err| {
+        eprintln!("Failed to load saved cookies, a new login is required: {err}");
+        CookieStore::default()
+    })
+}
+
+impl ExportedCookie {
+    fn to_raw(&self, now: i64) -> Option<RawCookie<'static>> {
+        let domain = self.domain.trim_start_matches('.');
+        let mut cookie = format!("{}={}; Path={}", self.name, self.value, self.path);
+        if self.host_only {
+            // host only cookies have no domain attribute, skip the other hosts here
+            if domain != "auth.riotgames.com" {
+                return None;
+            }
+        } else {
+            cookie.push_str(&format!("; Domain={domain}"));
+        }
+        if self.secure {
+            cookie.push_str("; Secure");
+        }
+        if let Some(expires) = self.expires {
+            cookie.push_str(&format!("; Max-Age={}", expires - now));
+        }
+
+        RawCookie::parse(cookie).ok()
+    }
+}
+
+fn parse_json(contents: &str) -> Result<Vec<ExportedCookie>, String> {
+    let export: JsonExport =
+        serde_json::from_str(contents).map_err(|err| format!("invalid JSON cookies: {err}"))?;
+    let cookies = match export {
+        JsonExport::Cookies(cookies) | JsonExport::StorageState { cookies } => cookies,
+    };
+
+    Ok(cookies
+        .into_iter()
+        .map(|cookie| ExportedCookie {
+            host_only: cookie.host_only,
+            path: cookie.path.unwrap_or_else(|| "/".to_string()),
+            secure: cookie.secure,
+            // playwright uses -1 for session cookies
+            expires: cookie
+                .expiration_date
+                .or(cookie.expires)
+                .filter(|expires| *expires > 0.0)
+                .map(|expires| expires as i64),
+            domain: cookie.domain,
+            name: cookie.name,
+            value: cookie.value,
+        })
+        .collect())
+}
+
+/// Parses `domain  include_subdomains  path  secure  expires  name  value` lines.
+fn parse_netscape(contents: &str) -> Vec<ExportedCookie> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            // curl marks http only cookies with a prefix instead of a column
+            let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+            if line.starts_with('#') || line.trim().is_empty() {
+                return None;
+            }
+
+            let fields: Vec<_> = line.split('\t').collect();
+            let [domain, subdomains, path, secure, expires, name, value] = fields[..] else {
+                return None;
+            };
+
+            Some(ExportedCookie {
+                domain: domain.to_string(),
+                host_only: !subdomains.eq_ignore_ascii_case("TRUE"),
+                path: path.to_string(),
+                secure: secure.eq_ignore_ascii_case("TRUE"),
+                expires: expires.parse().ok().filter(|expires| *expires > 0),
+                name: name.to_string(),
+                value: value.trim_end_matches(['\r', '\n']).to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sorted `name=value` pairs the jar would send to auth.riotgames.com.
+    fn auth_cookies(store: &CookieStore) -> Vec<String> {
+        let url = Url::parse(AUTH_URL).unwrap();
+        let mut cookies: Vec<_> = store
+            .get_request_values(&url)
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect();
+        cookies.sort();
+        cookies
+    }
+
+    #[test]
+    fn netscape_export() {
+        let contents = [
+            "# Netscape HTTP Cookie File",
+            "#HttpOnly_.riotgames.com\tTRUE\t/\tTRUE\t4102444800\tssid\tsession",
+            "auth.riotgames.com\tFALSE\t/\tTRUE\t0\tclid\tec1",
+            "riotgames.com\tFALSE\t/\tTRUE\t4102444800\thost_only\tother_host",
+            ".riotgames.com\tTRUE\t/\tTRUE\t1000000000\texpired\told",
+            ".example.com\tTRUE\t/\tFALSE\t4102444800\tunrelated\tx",
+        ]
+        .join("\n");
+
+        let store = import_auth_cookies(&contents).unwrap();
+
+        assert_eq!(auth_cookies(&store), ["clid=ec1", "ssid=session"]);
+    }
+
+    #[test]
+    fn playwright_storage_state() {
+        let contents = r#"{
+            "cookies": [
+                {"name": "ssid", "value": "session", "domain": ".riotgames.com", "path": "/",
+                 "expires": -1, "httpOnly": true, "secure": true, "sameSite": "None"},
+                {"name": "tdid", "value": "device", "domain": "auth.riotgames.com", "path": "/",
+                 "expires": 4102444800.5, "httpOnly": true, "secure": true, "sameSite": "None"}
+            ],
+            "origins": []
+        }"#;
+
+        let store = import_auth_cookies(contents).unwrap();
+
+        assert_eq!(auth_cookies(&store), ["ssid=session", "tdid=device"]);
+    }
+
+    #[test]
+    fn cookie_editor_export() {
+        let contents = r#"[
+            {"name": "ssid", "value": "session", "domain": ".riotgames.com", "path": "/",
+             "secure": true, "hostOnly": false, "expirationDate": 4102444800.0},
+            {"name": "clid", "value": "ec1", "domain": "auth.riotgames.com", "path": "/",
+             "secure": true, "hostOnly": true},
+            {"name": "host_only", "value": "other_host", "domain": "riotgames.com",
+             "hostOnly": true},
+            {"name": "expired", "value": "old", "domain": ".riotgames.com",
+             "expirationDate": 1000000000.0}
+        ]"#;
+
+        let store = import_auth_cookies(contents).unwrap();
+
+        assert_eq!(auth_cookies(&store), ["clid=ec1", "ssid=session"]);
+    }
+
+    #[test]
+    fn plain_header() {
+        let store = import_auth_cookies("ssid=session; clid=ec1;").unwrap();
+
+        assert_eq!(auth_cookies(&store), ["clid=ec1", "ssid=session"]);
+    }
+
+    #[test]
+    fn saved_jar_round_trip() {
+        let store = import_auth_cookies("ssid=session; clid=ec1").unwrap();
+
+        let saved = save_cookie_store(&store);
+
+        assert_eq!(
+            auth_cookies(&load_cookie_store(&saved)),
+            ["clid=ec1", "ssid=session"]
+        );
+    }
+
+    #[test]
+    fn expired_cookies_are_not_saved() {
+        let url = Url::parse(AUTH_URL).unwrap();
+        let cookies = [
+            "ssid=session; Path=/",
+            "tdid=device; Path=/; Expires=Wed, 01 Jan 2100 00:00:00 GMT",
+            "clid=old; Path=/; Expires=Sun, 09 Sep 2001 01:46:40 GMT",
+        ]
+        .map(|cookie| cookie_store::Cookie::parse(cookie, &url).map(|it| it.into_owned()));
+        let store = CookieStore::from_cookies(cookies, true).unwrap();
+
+        let saved = save_cookie_store(&store);
+        let loaded = cookie_store::serde::json::load_all(saved.as_bytes()).unwrap();
+
+        let mut names: Vec<_> = loaded.iter_any().map(|cookie| cookie.name()).collect();
+        names.sort();
+        assert_eq!(names, ["ssid", "tdid"]);
+    }
+
+    #[test]
+    fn legacy_header_session() {
+        assert_eq!(
+            auth_cookies(&load_cookie_store("ssid=session; clid=ec1")),
+            ["clid=ec1", "ssid=session"]
+        );
+    }
+
+    #[test]
+    fn corrupted_jar_is_not_read_as_header() {
+        assert!(auth_cookies(&load_cookie_store("[{\"raw_cookie\": 1}]")).is_empty());
+    }
+
+    #[test]
+    fn export_without_auth_cookies() {
+        let contents = ".example.com\tTRUE\t/\tFALSE\t4102444800\tunrelated\tx";
+
+        assert!(import_auth_cookies(contents).is_err());
+    }
+
+    #[test]
+    fn empty_header() {
+        assert_eq!(
+            import_auth_cookies(" ; ").err().as_deref(),
+            Some("no auth.riotgames.com cookies found")
+        );
+    }
+}
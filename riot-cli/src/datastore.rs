@@ -1,19 +1,27 @@
-use std::{fs, i64, path::PathBuf};
+use std::{cell::RefCell, collections::HashMap, fs, i64, path::PathBuf};
 
 use chrono::{DateTime, Utc};
 use directories::ProjectDirs;
-use rusqlite::{params, Connection, Result};
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use val_api::region::Region;
 
-use crate::models::{AddUser, AddUserSession, User, UserSession};
+use crate::{
+    cookies::{load_cookie_store, save_cookie_store},
+    models::{AddUser, AddUserSession, CachedName, User, UserRank, UserSession},
+};
 
 pub struct Datastore {
     conn: Connection,
+    names: RefCell<HashMap<String, CachedName>>,
 }
 
 impl Datastore {
     pub fn initialize() -> Result<Self> {
         let conn = Connection::open(Datastore::get_database_path())?;
-        let store = Self { conn };
+        let store = Self {
+            conn,
+            names: RefCell::new(HashMap::new()),
+        };
         store.migrate()?;
 
         Ok(store)
@@ -39,34 +47,86 @@ impl Datastore {
                 &session.access_token,
                 &session.id_token,
                 session.expires_at.timestamp_nanos_opt().unwrap_or(i64::MAX),
-                &session.authorized_cookies,
+                save_cookie_store(&session.authorized_cookies),
             ],
         ).map(|_| ())
     }
 
+    pub fn set_cookie_expiry_alerted(
+        &self,
+        user_id: &i64,
+        expires_at: &DateTime<Utc>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE sessions
+            SET cookie_expiry_alerted = ?2
+            WHERE user_id = ?1",
+            params![
+                &user_id,
+                &expires_at.timestamp_nanos_opt().unwrap_or(i64::MAX),
+            ],
+        )?;
+
+        Ok(())
+    }
+
     pub fn add_user(&self, user: &AddUser) -> Result<i64> {
         self.conn.query_row(
-            "INSERT INTO users (puuid, game_name, tag_line, region, user_info, entitlements_token, next_store, next_nightmarket)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "INSERT INTO users (puuid, game_name, tag_line, region, user_info, entitlements_token, next_store, next_nightmarket, next_bundle, next_accessory_store, entitlements_expires_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
             ON CONFLICT (puuid)
             DO
                 UPDATE
-                SET game_name = ?2, tag_line = ?3, region = ?4, user_info = ?5, entitlements_token = ?6, next_store = ?7, next_nightmarket = ?8
+                SET game_name = ?2, tag_line = ?3, region = ?4, user_info = ?5, entitlements_token = ?6, next_store = ?7, next_nightmarket = ?8, next_bundle = ?9, next_accessory_store = ?10, entitlements_expires_at = ?11, needs_reauth = 0
             RETURNING id",
             params![
                 &user.puuid,
                 &user.game_name,
                 &user.tag_line,
-                &user.region,
+                user.region.as_str(),
                 &user.user_info,
                 &user.entitlements_token,
                 &user.next_store.timestamp_nanos_opt().unwrap_or(i64::MAX),
                 &user.next_nightmarket.timestamp_nanos_opt().unwrap_or(i64::MAX),
+                &user.next_bundle.timestamp_nanos_opt().unwrap_or(i64::MAX),
+                &user.next_accessory_store.timestamp_nanos_opt().unwrap_or(i64::MAX),
+                &user.entitlements_expires_at.timestamp_nanos_opt().unwrap_or(i64::MAX),
             ],
             |row| row.get(0),
         )
     }
 
+    pub fn set_user_entitlements_token(
+        &self,
+        user_id: &i64,
+        entitlements_token: &str,
+        expires_at: &DateTime<Utc>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE users
+            SET entitlements_token = ?2, entitlements_expires_at = ?3
+            WHERE id = ?1",
+            params![
+                &user_id,
+                &entitlements_token,
+                &expires_at.timestamp_nanos_opt().unwrap_or(i64::MAX),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn set_user_needs_reauth(&self, user_id: &i64, needs_reauth: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE users
+            SET needs_reauth = ?2
+            WHERE id = ?1",
+            params![&user_id, &needs_reauth],
+        )?;
+
+        Ok(())
+    }
+
     pub fn set_user_next_store(&self, user_id: &i64, next_store: &DateTime<Utc>) -> Result<()> {
         self.conn.execute(
             "UPDATE users
@@ -99,6 +159,40 @@ impl Datastore {
         Ok(())
     }
 
+    pub fn set_user_next_bundle(&self, user_id: &i64, next_bundle: &DateTime<Utc>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE users
+            SET next_bundle = ?2
+            WHERE id = ?1",
+            params![
+                &user_id,
+                &next_bundle.timestamp_nanos_opt().unwrap_or(i64::MAX),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn set_user_next_accessory_store(
+        &self,
+        user_id: &i64,
+        next_accessory_store: &DateTime<Utc>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE users
+            SET next_accessory_store = ?2
+            WHERE id = ?1",
+            params![
+                &user_id,
+                &next_accessory_store
+                    .timestamp_nanos_opt()
+                    .unwrap_or(i64::MAX),
+            ],
+        )?;
+
+        Ok(())
+    }
+
     pub fn add_webhook(&self, url: &str) -> Result<()> {
         self.conn.execute(
             "INSERT INTO discord_webhooks (url)
@@ -120,41 +214,216 @@ impl Datastore {
         urls.collect()
     }
 
+    /// Enabled users, the ones commands should work with.
     pub fn get_users(&self) -> Result<Vec<User>> {
+        self.query_users(false)
+    }
+
+    pub fn get_all_users(&self) -> Result<Vec<User>> {
+        self.query_users(true)
+    }
+
+    fn query_users(&self, include_disabled: bool) -> Result<Vec<User>> {
         let mut stmt = self.conn.prepare(
-            "SELECT u.id, u.puuid, u.game_name, u.tag_line, u.region, u.user_info, u.entitlements_token, u.next_store, u.next_nightmarket, s.id as session_id, s.access_token, s.id_token, s.expires_at, s.authenticated_cookies
+            "SELECT u.id, u.puuid, u.game_name, u.tag_line, u.region, u.user_info, u.entitlements_token, u.next_store, u.next_nightmarket, u.next_bundle, u.next_accessory_store, u.entitlements_expires_at, u.needs_reauth, u.disabled, u.alias, s.id as session_id, s.access_token, s.id_token, s.expires_at, s.authenticated_cookies, s.cookie_expiry_alerted
             FROM users u
-            LEFT JOIN sessions s ON s.user_id = u.id")?;
+            LEFT JOIN sessions s ON s.user_id = u.id
+            WHERE ?1 OR u.disabled = 0")?;
 
-        let users = stmt.query_map([], |row| {
-            Ok(User {
+        let users = stmt.query_map([include_disabled], |row| {
+            // one unreadable account must not make every other account unusable
+            let region: String = row.get(4)?;
+            let Ok(region) = region.parse::<Region>() else {
+                eprintln!(
+                    "Skipping account {}#{}, unknown region {region}, add it again to fix it",
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?
+                );
+                return Ok(None);
+            };
+
+            Ok(Some(User {
                 id: row.get(0)?,
                 puuid: row.get(1)?,
                 game_name: row.get(2)?,
                 tag_line: row.get(3)?,
-                region: row.get(4)?,
+                region,
                 user_info: row.get(5)?,
                 entitlements_token: row.get(6)?,
                 next_store: DateTime::from_timestamp_nanos(row.get(7)?),
                 next_nightmarket: DateTime::from_timestamp_nanos(row.get(8)?),
-                session: match row.get(9)? {
+                next_bundle: DateTime::from_timestamp_nanos(row.get(9)?),
+                next_accessory_store: DateTime::from_timestamp_nanos(row.get(10)?),
+                entitlements_expires_at: DateTime::from_timestamp_nanos(row.get(11)?),
+                needs_reauth: row.get(12)?,
+                disabled: row.get(13)?,
+                alias: row.get(14)?,
+                session: match row.get(15)? {
                     Some(id) => Some(UserSession {
                         id,
                         user_id: row.get(0)?,
-                        access_token: row.get(10)?,
-                        id_token: row.get(11)?,
-                        expires_at: DateTime::from_timestamp_nanos(row.get(12)?),
-                        authorized_cookies: row.get(13)?,
+                        access_token: row.get(16)?,
+                        id_token: row.get(17)?,
+                        expires_at: DateTime::from_timestamp_nanos(row.get(18)?),
+                        authorized_cookies: load_cookie_store(&row.get::<_, String>(19)?),
+                        cookie_expiry_alerted: row
+                            .get::<_, Option<i64>>(20)?
+                            .map(DateTime::from_timestamp_nanos),
                     }),
                     None => None,
                 },
-            })
+            }))
         })?;
 
-        users.collect()
+        users.filter_map(Result::transpose).collect()
+    }
+
+    /// Removes the user along with its session and stored rank.
+    pub fn remove_user(&self, user_id: &i64) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM sessions WHERE user_id = ?1", [user_id])?;
+        tx.execute("DELETE FROM ranks WHERE user_id = ?1", [user_id])?;
+        tx.execute("DELETE FROM users WHERE id = ?1", [user_id])?;
+        tx.commit()
+    }
+
+    pub fn set_user_disabled(&self, user_id: &i64, disabled: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE users
+            SET disabled = ?2
+            WHERE id = ?1",
+            params![&user_id, &disabled],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn set_user_alias(&self, user_id: &i64, alias: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE users
+            SET alias = ?2
+            WHERE id = ?1",
+            params![&user_id, &alias],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn get_rank(&self, user_id: &i64) -> Result<Option<UserRank>> {
+        self.conn
+            .query_row(
+                "SELECT user_id, match_id, competitive_tier, ranked_rating, updated_at
+                FROM ranks
+                WHERE user_id = ?1",
+                [user_id],
+                |row| {
+                    Ok(UserRank {
+                        user_id: row.get(0)?,
+                        match_id: row.get(1)?,
+                        competitive_tier: row.get(2)?,
+                        ranked_rating: row.get(3)?,
+                        updated_at: DateTime::from_timestamp_nanos(row.get(4)?),
+                    })
+                },
+            )
+            .optional()
+    }
+
+    pub fn set_rank(&self, rank: &UserRank) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO ranks (user_id, match_id, competitive_tier, ranked_rating, updated_at)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                ON CONFLICT (user_id)
+                DO
+                    UPDATE
+                    SET match_id = ?2, competitive_tier = ?3, ranked_rating = ?4, updated_at = ?5",
+                params![
+                    &rank.user_id,
+                    &rank.match_id,
+                    &rank.competitive_tier,
+                    &rank.ranked_rating,
+                    rank.updated_at.timestamp_nanos_opt().unwrap_or(i64::MAX),
+                ],
+            )
+            .map(|_| ())
+    }
+
+    /// Cached names of the given players, from memory first and then from the database.
+    pub fn get_player_names(&self, puuids: &[String]) -> Result<HashMap<String, CachedName>> {
+        let mut names = self.names.borrow_mut();
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT puuid, game_name, tag_line, updated_at
+            FROM player_names
+            WHERE puuid = ?1",
+        )?;
+
+        let mut found = HashMap::new();
+        for puuid in puuids {
+            if let Some(name) = names.get(puuid) {
+                found.insert(puuid.clone(), name.clone());
+                continue;
+            }
+
+            let name = stmt
+                .query_row([puuid], |row| {
+                    Ok(CachedName {
+                        puuid: row.get(0)?,
+                        game_name: row.get(1)?,
+                        tag_line: row.get(2)?,
+                        updated_at: DateTime::from_timestamp_nanos(row.get(3)?),
+                    })
+                })
+                .optional()?;
+
+            if let Some(name) = name {
+                names.insert(puuid.clone(), name.clone());
+                found.insert(puuid.clone(), name);
+            }
+        }
+
+        Ok(found)
+    }
+
+    pub fn set_player_names(&self, names: &[CachedName]) -> Result<()> {
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO player_names (puuid, game_name, tag_line, updated_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT (puuid)
+            DO
+                UPDATE
+                SET game_name = ?2, tag_line = ?3, updated_at = ?4",
+        )?;
+
+        let mut cache = self.names.borrow_mut();
+        for name in names {
+            stmt.execute(params![
+                &name.puuid,
+                &name.game_name,
+                &name.tag_line,
+                name.updated_at.timestamp_nanos_opt().unwrap_or(i64::MAX),
+            ])?;
+            cache.insert(name.puuid.clone(), name.clone());
+        }
+
+        Ok(())
     }
 
     fn get_database_version(&self) -> Result<usize> {
+        let initialized: bool = self.conn.query_row(
+            "SELECT EXISTS (
+                SELECT 1
+                FROM sqlite_master
+                WHERE type = 'table' AND name = 'version'
+            )",
+            [],
+            |row| row.get(0),
+        )?;
+
+        if !initialized {
+            return Ok(0);
+        }
+
         let version: String = self.conn.query_row(
             "SELECT version
             FROM version",
@@ -177,6 +446,34 @@ impl Datastore {
             self.conn
                 .execute_batch(include_str!("../migrations/01_initialize.sql"))?;
         }
+        if version < 2 {
+            self.conn
+                .execute_batch(include_str!("../migrations/02_store_rotations.sql"))?;
+        }
+        if version < 3 {
+            self.conn
+                .execute_batch(include_str!("../migrations/03_ranks.sql"))?;
+        }
+        if version < 4 {
+            self.conn
+                .execute_batch(include_str!("../migrations/04_player_names.sql"))?;
+        }
+        if version < 5 {
+            self.conn
+                .execute_batch(include_str!("../migrations/05_entitlements_expiry.sql"))?;
+        }
+        if version < 6 {
+            self.conn
+                .execute_batch(include_str!("../migrations/06_cookie_expiry_alerts.sql"))?;
+        }
+        if version < 7 {
+            self.conn
+                .execute_batch(include_str!("../migrations/07_needs_reauth.sql"))?;
+        }
+        if version < 8 {
+            self.conn
+                .execute_batch(include_str!("../migrations/08_account_management.sql"))?;
+        }
 
         Ok(())
     }
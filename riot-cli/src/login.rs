@@ -0,0 +1,212 @@
+use std::{
+    cell::Cell,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use cookie_store::CookieStore;
+use val_api::{
+    client::ValClient,
+    endpoints::auth::{
+        password_login, silent_login, submit_multifactor_code, tokens_from_redirect,
+        PasswordLoginOutcome, SilentLoginOutcome, RIOT_AUTH_PAGE, RIOT_LOGIN_PROMPT_PAGE,
+    },
+    models::Tokens,
+};
+
+use crate::cookies::{cookie_store_from_header, import_auth_cookies};
+
+/// A way to get tokens and session cookies for a new or expired account.
+pub trait LoginProvider {
+    /// Logs in, `None` when the login failed or was cancelled after
+    /// telling the user why.
+    async fn login(&self, client: &ValClient) -> Option<(Tokens, CookieStore)>;
+
+    /// Makes the next login ask for the credentials instead of reusing a
+    /// session the provider kept, e.g. after logging in on the wrong account.
+    fn forget_session(&self) {}
+}
+
+/// Login used when a session expired, the popup when it is available and
+/// the pasted redirect everywhere else.
+#[cfg(all(windows, feature = "webview2"))]
+pub type InteractiveLogin = PopupLogin;
+#[cfg(not(all(windows, feature = "webview2")))]
+pub type InteractiveLogin = PastedRedirectLogin;
+
+/// Riot login page in a WebView2 window, Windows only.
+#[cfg(all(windows, feature = "webview2"))]
+#[derive(Default)]
+pub struct PopupLogin {
+    prompt_login: Cell<bool>,
+}
+
+#[cfg(all(windows, feature = "webview2"))]
+impl LoginProvider for PopupLogin {
+    async fn login(&self, _client: &ValClient) -> Option<(Tokens, CookieStore)> {
+        let project =
+            directories::ProjectDirs::from("", "", "riot-cli").expect("project dirs should work");
+        let folder = project.data_dir().join("edge-profile");
+
+        // the profile stays signed in, only the login prompt lets the user switch accounts
+        let login = val_login_webview2::login_popup(&folder, auth_page(&self.prompt_login));
+        if login.is_none() {
+            eprintln!("Login window closed before logging in");
+        }
+        login.map(|(tokens, cookies)| (tokens, cookie_store_from_header(&cookies)))
+    }
+
+    fn forget_session(&self) {
+        self.prompt_login.set(true);
+    }
+}
+
+/// Username and password, asking for the password and multifactor code on the terminal.
+pub struct PasswordLogin {
+    pub username: String,
+}
+
+impl LoginProvider for PasswordLogin {
+    async fn login(&self, client: &ValClient) -> Option<(Tokens, CookieStore)> {
+        let username = &self.username;
+        let password = rpassword::prompt_password(format!("Password for {username}: ")).ok()?;
+
+        let mut outcome = password_login(client, username, &password).await;
+        loop {
+            match outcome {
+                Ok(PasswordLoginOutcome::LoggedIn { tokens, cookies }) => {
+                    return Some((tokens, cookies))
+                }
+                Ok(PasswordLoginOutcome::MultifactorRequired(challenge)) => {
+                    let code = match &challenge.details.email {
+                        Some(email) => read_line(&format!("Code sent to {email}: "))?,
+                        None => read_line("Multifactor code: ")?,
+                    };
+                    outcome = submit_multifactor_code(client, challenge, &code).await;
+                }
+                Ok(PasswordLoginOutcome::Failed { error }) => {
+                    eprintln!("Login rejected: {error}");
+                    return None;
+                }
+                Err(err) => {
+                    eprintln!("Login failed: {err}");
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// Login done in any browser, the user pastes the redirect url and cookies.
+#[derive(Default)]
+pub struct PastedRedirectLogin {
+    prompt_login: Cell<bool>,
+}
+
+impl LoginProvider for PastedRedirectLogin {
+    async fn login(&self, client: &ValClient) -> Option<(Tokens, CookieStore)> {
+        println!("Log in on the following page:");
+        println!("{}", auth_page(&self.prompt_login));
+        println!(
+            "then paste the url you end up on (https://playvalorant.com/opt_in#access_token=...)"
+        );
+
+        let tokens = loop {
+            let url = read_line("Url: ")?;
+            match tokens_from_redirect(&url) {
+                Some(tokens) => break tokens,
+                None => eprintln!("No tokens found in the url, copy the full address"),
+            }
+        };
+
+        println!("Paste the cookies of auth.riotgames.com (name=value; name=value)");
+        let cookies = cookie_store_from_header(&read_line("Cookies: ")?);
+
+        // refreshing right away checks the cookies and gives the most recent tokens
+        match silent_login(client, &cookies).await {
+            Ok(SilentLoginOutcome::LoggedIn { tokens, cookies }) => Some((tokens, cookies)),
+            Ok(outcome) => {
+                eprintln!(
+                    "The cookies cannot refresh the session ({outcome:?}), it will expire with the tokens"
+                );
+                Some((tokens, cookies))
+            }
+            Err(err) => {
+                eprintln!("Failed to check the cookies: {err}");
+                Some((tokens, cookies))
+            }
+        }
+    }
+
+    fn forget_session(&self) {
+        self.prompt_login.set(true);
+    }
+}
+
+/// Cookies of an existing auth.riotgames.com session read from a browser export.
+pub struct ImportedCookiesLogin {
+    pub path: PathBuf,
+}
+
+impl LoginProvider for ImportedCookiesLogin {
+    async fn login(&self, client: &ValClient) -> Option<(Tokens, CookieStore)> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                eprintln!("Failed to read {}: {err}", self.path.display());
+                return None;
+            }
+        };
+        let cookies = match import_auth_cookies(&contents) {
+            Ok(cookies) => cookies,
+            Err(err) => {
+                eprintln!("Failed to import {}: {err}", self.path.display());
+                return None;
+            }
+        };
+
+        match silent_login(client, &cookies).await {
+            Ok(SilentLoginOutcome::LoggedIn { tokens, cookies }) => Some((tokens, cookies)),
+            Ok(outcome) => {
+                eprintln!("The cookies do not hold a valid session: {outcome:?}");
+                None
+            }
+            Err(err) => {
+                eprintln!("Failed to log in with the cookies: {err}");
+                None
+            }
+        }
+    }
+}
+
+/// Login page, asking for the credentials once the session was forgotten.
+fn auth_page(prompt_login: &Cell<bool>) -> &'static str {
+    if prompt_login.get() {
+        RIOT_LOGIN_PROMPT_PAGE
+    } else {
+        RIOT_AUTH_PAGE
+    }
+}
+
+/// Asks a yes/no question, yes unless the answer starts with `n`.
+pub fn confirm(prompt: &str) -> bool {
+    read_line(prompt).is_some_and(|answer| !answer.to_lowercase().starts_with('n'))
+}
+
+/// Asks a yes/no question, no unless the answer starts with `y`.
+pub fn confirm_default_no(prompt: &str) -> bool {
+    read_line(prompt).is_some_and(|answer| answer.to_lowercase().starts_with('y'))
+}
+
+fn read_line(prompt: &str) -> Option<String> {
+    print!("{prompt}");
+    io::stdout().flush().ok()?;
+
+    let mut line = String::new();
+    // 0 bytes read means stdin was closed
+    match io::stdin().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim().to_string()),
+    }
+}
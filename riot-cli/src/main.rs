@@ -1,8 +1,16 @@
+mod accounts;
+mod cookies;
 mod datastore;
+mod login;
 mod models;
+mod val;
 mod val_store;
 
-use clap::{command, Parser, Subcommand};
+use std::{path::PathBuf, time::Duration};
+
+use clap::{command, Args, Parser, Subcommand};
+use val::handle_val_command;
+use val_api::{client::ValClient, retry::RetryPolicy};
 use val_store::handle_val_store_command;
 
 #[derive(Parser, Debug)]
@@ -10,6 +18,37 @@ use val_store::handle_val_store_command;
 struct Cli {
     #[command(subcommand)]
     pub module: Module,
+    #[command(flatten)]
+    pub retry: RetryArgs,
+}
+
+/// Retry behaviour of failed and rate limited requests
+#[derive(Args, Debug)]
+struct RetryArgs {
+    /// Total attempts per request, 1 disables retries
+    #[clap(long, global = true, default_value_t = 3)]
+    max_attempts: u32,
+    /// Delay before the first retry, doubled on every attempt
+    #[clap(long, global = true, default_value_t = 500)]
+    retry_delay_ms: u64,
+    /// Longest delay between attempts, longer Retry-After values give up
+    #[clap(long, global = true, default_value_t = 30)]
+    max_retry_delay_secs: u64,
+    /// Disable the random jitter added to the backoff
+    #[clap(long, global = true)]
+    no_jitter: bool,
+}
+
+impl From<&RetryArgs> for RetryPolicy {
+    fn from(args: &RetryArgs) -> Self {
+        RetryPolicy {
+            max_attempts: args.max_attempts.max(1),
+            base_delay: Duration::from_millis(args.retry_delay_ms),
+            max_delay: Duration::from_secs(args.max_retry_delay_secs),
+            jitter: !args.no_jitter,
+            ..RetryPolicy::default()
+        }
+    }
 }
 
 #[derive(Subcommand, Debug)]
@@ -18,26 +57,102 @@ enum Module {
         #[command(subcommand)]
         action: ValStoreCommands,
     },
+    Val {
+        #[command(subcommand)]
+        action: ValCommands,
+    },
 }
 
 #[derive(Subcommand, Debug)]
 enum ValStoreCommands {
-    Add,
+    Add {
+        /// Log in with a username and password instead of the login window
+        #[clap(long, short)]
+        username: Option<String>,
+        /// Log in through a browser and paste the redirect url and cookies
+        #[clap(long, conflicts_with_all = ["username", "cookies"])]
+        paste: bool,
+        /// Reuse the session of a cookies.txt or JSON cookies export
+        #[clap(long, conflicts_with = "username")]
+        cookies: Option<PathBuf>,
+    },
     Check {
         #[clap(long, short)]
         force: bool,
         #[clap(long)]
         force_nightmarket: bool,
+        /// Alert the webhooks this many days before an account needs a new login, 0 disables it
+        #[clap(long, default_value_t = 3)]
+        reauth_alert_days: i64,
+        /// Never open a login, accounts needing one are reported to the webhooks instead.
+        /// Default when stdin is not a terminal
+        #[clap(long)]
+        non_interactive: bool,
+    },
+    /// List when the tokens and login cookie of every account expire
+    Sessions,
+    Accounts {
+        #[command(subcommand)]
+        action: AccountCommands,
     },
     Webhook {
         uri: String,
     },
+    Wallet,
+    Inventory,
+    Price {
+        name: String,
+    },
+}
+
+/// Accounts are given as `Name#Tag`, alias, puuid or id
+#[derive(Subcommand, Debug)]
+enum AccountCommands {
+    List,
+    /// Remove the account and its session
+    Remove {
+        account: String,
+        /// Remove without asking for confirmation
+        #[clap(long, short)]
+        yes: bool,
+    },
+    /// Keep the account but skip it in every command
+    Disable {
+        account: String,
+    },
+    Enable {
+        account: String,
+    },
+    /// Set the alias of the account, clears it when omitted
+    Alias {
+        account: String,
+        alias: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ValCommands {
+    Match {
+        #[clap(long, short, default_value_t = 5)]
+        count: u32,
+        #[clap(long, short)]
+        queue: Option<String>,
+        #[clap(long, short)]
+        players: bool,
+    },
+    Mmr,
+    Loadout,
+    Act,
 }
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    let mut client = ValClient::new();
+    client.set_retry_policy(RetryPolicy::from(&cli.retry));
+
     match &cli.module {
-        Module::ValStore { action } => handle_val_store_command(action).await,
+        Module::ValStore { action } => handle_val_store_command(action, &client).await,
+        Module::Val { action } => handle_val_command(action, &client).await,
     };
 }
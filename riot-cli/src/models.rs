@@ -1,15 +1,20 @@
 use chrono::{DateTime, Utc};
+use cookie_store::CookieStore;
+use val_api::{endpoints::auth::session_cookie_expires_at, region::Region};
 
 #[derive(Debug)]
 pub struct AddUser {
     pub puuid: String,
     pub game_name: String,
     pub tag_line: String,
-    pub region: String,
+    pub region: Region,
     pub user_info: String,
     pub entitlements_token: String,
+    pub entitlements_expires_at: DateTime<Utc>,
     pub next_store: DateTime<Utc>,
     pub next_nightmarket: DateTime<Utc>,
+    pub next_bundle: DateTime<Utc>,
+    pub next_accessory_store: DateTime<Utc>,
 }
 
 #[derive(Debug)]
@@ -18,7 +23,7 @@ pub struct AddUserSession {
     pub access_token: String,
     pub id_token: String,
     pub expires_at: DateTime<Utc>,
-    pub authorized_cookies: String,
+    pub authorized_cookies: CookieStore,
 }
 
 #[derive(Debug)]
@@ -28,11 +33,19 @@ pub struct User {
     pub puuid: String,
     pub game_name: String,
     pub tag_line: String,
-    pub region: String,
+    pub region: Region,
     pub user_info: String,
     pub entitlements_token: String,
+    pub entitlements_expires_at: DateTime<Utc>,
     pub next_store: DateTime<Utc>,
     pub next_nightmarket: DateTime<Utc>,
+    pub next_bundle: DateTime<Utc>,
+    pub next_accessory_store: DateTime<Utc>,
+    /// Set when the session could not be refreshed and nobody was there to log in.
+    pub needs_reauth: bool,
+    /// Disabled accounts are kept but skipped by every command.
+    pub disabled: bool,
+    pub alias: Option<String>,
     pub session: Option<UserSession>,
 }
 
@@ -44,5 +57,31 @@ pub struct UserSession {
     pub access_token: String,
     pub id_token: String,
     pub expires_at: DateTime<Utc>,
-    pub authorized_cookies: String,
+    pub authorized_cookies: CookieStore,
+    /// Cookie expiry the re-login alert was last sent for.
+    pub cookie_expiry_alerted: Option<DateTime<Utc>>,
+}
+
+impl UserSession {
+    /// When the login cookie expires and an interactive login becomes necessary.
+    pub fn cookies_expire_at(&self) -> Option<DateTime<Utc>> {
+        session_cookie_expires_at(&self.authorized_cookies)
+    }
+}
+
+#[derive(Debug)]
+pub struct UserRank {
+    pub user_id: i64,
+    pub match_id: String,
+    pub competitive_tier: u32,
+    pub ranked_rating: u32,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedName {
+    pub puuid: String,
+    pub game_name: String,
+    pub tag_line: String,
+    pub updated_at: DateTime<Utc>,
 }
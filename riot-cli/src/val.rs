@@ -0,0 +1,578 @@
+use std::{
+    collections::HashMap,
+    io::{self, IsTerminal},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use val_api::{
+    client::ValClient,
+    endpoints::{content, matches, mmr, name_service, personalization},
+    models::{CompetitiveUpdate, EmbedFooter, MatchPlayer, MessageEmbed, WebhookMessage},
+    rank::tier_name,
+    region::Region,
+    season::{self, Act},
+    thirdparty,
+};
+
+use crate::{
+    datastore::Datastore,
+    models::{CachedName, User, UserRank},
+    val_store::{
+        catalog_or_empty, refresh_expired_accounts, send_webhooks, usable_session,
+        with_client_version,
+    },
+    ValCommands,
+};
+
+const NAME_CACHE_DURATION: Duration = Duration::days(7);
+const COMPETITIVE_UPDATES_PAGE: u32 = 10;
+/// Competitive updates searched for the last stored match before giving up.
+const COMPETITIVE_UPDATES_LIMIT: u32 = 100;
+
+pub async fn handle_val_command(command: &ValCommands, client: &ValClient) {
+    let datastore = Datastore::initialize().expect("database should initialize");
+    let client = client.clone();
+
+    match command {
+        ValCommands::Match {
+            count,
+            queue,
+            players,
+        } => match_history(&datastore, &client, *count, queue.as_deref(), *players).await,
+        ValCommands::Mmr => ranks(&datastore, &client).await,
+        ValCommands::Loadout => loadout(&datastore, &client).await,
+        ValCommands::Act => current_act(&datastore, &client).await,
+    };
+}
+
+pub async fn match_history(
+    db: &Datastore,
+    client: &ValClient,
+    count: u32,
+    queue: Option<&str>,
+    players: bool,
+) {
+    refresh_expired_accounts(db, client, io::stdin().is_terminal()).await;
+
+    // without a catalog the agents and maps are shown by their ids
+    let agents: HashMap<String, String> = match thirdparty::valdata::get_agents(client).await {
+        Ok(agents) => agents
+            .into_iter()
+            .filter_map(|agent| Some((agent.uuid.to_lowercase(), agent.display_name?)))
+            .collect(),
+        Err(err) => {
+            eprintln!("Failed to load agent catalog: {err}");
+            HashMap::new()
+        }
+    };
+    let maps: HashMap<String, String> = match thirdparty::valdata::get_maps(client).await {
+        Ok(maps) => maps
+            .into_iter()
+            .filter_map(|map| Some((map.map_url?, map.display_name?)))
+            .collect(),
+        Err(err) => {
+            eprintln!("Failed to load map catalog: {err}");
+            HashMap::new()
+        }
+    };
+    let client = with_client_version(client).await;
+
+    let users = db.get_users().expect("users");
+    for user in users {
+        let Some(session) = usable_session(&user) else {
+            continue;
+        };
+
+        let client = client.with_tokens(&session.access_token, &user.entitlements_token);
+        let history =
+            match matches::match_fetch_history(&client, user.region, &user.puuid, 0, count, queue)
+                .await
+            {
+                Ok(history) => history,
+                Err(err) => {
+                    eprintln!(
+                        "Failed to fetch match history of {}#{}: {err}",
+                        user.game_name, user.tag_line
+                    );
+                    continue;
+                }
+            };
+
+        println!("{}#{}", user.game_name, user.tag_line);
+        for entry in history.history {
+            let details =
+                match matches::match_fetch_details(&client, user.region, &entry.match_id).await {
+                    Ok(details) => details,
+                    Err(err) => {
+                        eprintln!("  Failed to fetch match {}: {err}", entry.match_id);
+                        continue;
+                    }
+                };
+
+            let Some(player) = details.player(&user.puuid) else {
+                continue;
+            };
+
+            let started = DateTime::from_timestamp_millis(details.match_info.game_start_millis)
+                .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            let map = maps
+                .get(&details.match_info.map_id)
+                .map(String::as_str)
+                .unwrap_or(&details.match_info.map_id);
+            let agent = player
+                .character_id
+                .as_ref()
+                .map_or("?".to_string(), |id| resolve_name(&agents, id));
+            let score = match details.score(&player.team_id) {
+                Some((won, lost)) => format!("{won}-{lost}"),
+                None => "-".to_string(),
+            };
+            let kda = format_kda(player);
+
+            println!(
+                "  {started} {} {map} {agent} {score} {kda}",
+                details.match_info.queue_id
+            );
+
+            if players {
+                let puuids: Vec<_> = details
+                    .players
+                    .iter()
+                    .map(|player| player.subject.clone())
+                    .collect();
+                let names = resolve_player_names(db, &client, user.region, &puuids).await;
+
+                let mut scoreboard: Vec<_> = details.players.iter().collect();
+                scoreboard.sort_by_key(|player| {
+                    (
+                        player.team_id.clone(),
+                        std::cmp::Reverse(player.stats.as_ref().map(|stats| stats.score)),
+                    )
+                });
+                for player in scoreboard {
+                    let agent = player
+                        .character_id
+                        .as_ref()
+                        .map_or("?".to_string(), |id| resolve_name(&agents, id));
+                    println!(
+                        "    [{}] {} {agent} {}",
+                        player.team_id,
+                        names.get(&player.subject).unwrap_or(&player.subject),
+                        format_kda(player),
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn format_kda(player: &MatchPlayer) -> String {
+    match &player.stats {
+        Some(stats) => format!("{}/{}/{}", stats.kills, stats.deaths, stats.assists),
+        None => "-".to_string(),
+    }
+}
+
+/// `GameName#TagLine` of the players, using the datastore cache before the name service.
+pub async fn resolve_player_names(
+    db: &Datastore,
+    client: &ValClient,
+    region: Region,
+    puuids: &[String],
+) -> HashMap<String, String> {
+    let cached = db
+        .get_player_names(puuids)
+        .expect("player names should load");
+
+    let mut missing: Vec<_> = puuids
+        .iter()
+        .filter(|puuid| {
+            cached
+                .get(*puuid)
+                .is_none_or(|name| name.updated_at + NAME_CACHE_DURATION < Utc::now())
+        })
+        .cloned()
+        .collect();
+    missing.sort();
+    missing.dedup();
+
+    let mut names: HashMap<_, _> = cached
+        .into_values()
+        .map(|name| (name.puuid, format!("{}#{}", name.game_name, name.tag_line)))
+        .collect();
+
+    if missing.is_empty() {
+        return names;
+    }
+
+    match name_service::name_fetch_players(client, region, &missing).await {
+        Ok(players) => {
+            let fresh: Vec<_> = players
+                .into_iter()
+                .filter(|player| !player.game_name.is_empty())
+                .map(|player| CachedName {
+                    puuid: player.subject,
+                    game_name: player.game_name,
+                    tag_line: player.tag_line,
+                    updated_at: Utc::now(),
+                })
+                .collect();
+            db.set_player_names(&fresh)
+                .expect("player names should be saved");
+
+            names.extend(
+                fresh
+                    .into_iter()
+                    .map(|name| (name.puuid, format!("{}#{}", name.game_name, name.tag_line))),
+            );
+        }
+        Err(err) => eprintln!("Failed to resolve player names: {err}"),
+    }
+
+    names
+}
+
+pub async fn ranks(db: &Datastore, client: &ValClient) {
+    refresh_expired_accounts(db, client, io::stdin().is_terminal()).await;
+
+    let client = with_client_version(client).await;
+    let users = db.get_users().expect("users");
+    let webhooks = db.get_webhooks().expect("webhooks");
+    let act = load_current_act(&client, &users).await;
+
+    for user in users {
+        let Some(session) = usable_session(&user) else {
+            continue;
+        };
+
+        let client = client.with_tokens(&session.access_token, &user.entitlements_token);
+        let player = match mmr::mmr_fetch_player(&client, user.region, &user.puuid).await {
+            Ok(player) => player,
+            Err(err) => {
+                eprintln!(
+                    "Failed to fetch mmr of {}#{}: {err}",
+                    user.game_name, user.tag_line
+                );
+                continue;
+            }
+        };
+        let updates = match mmr::mmr_fetch_competitive_updates(
+            &client,
+            user.region,
+            &user.puuid,
+            0,
+            COMPETITIVE_UPDATES_PAGE,
+        )
+        .await
+        {
+            Ok(updates) => updates.matches,
+            Err(err) => {
+                eprintln!(
+                    "Failed to fetch competitive updates of {}#{}: {err}",
+                    user.game_name, user.tag_line
+                );
+                continue;
+            }
+        };
+
+        let Some(latest) = updates
+            .first()
+            .or(player.latest_competitive_update.as_ref())
+        else {
+            println!(
+                "{}#{}: no competitive matches",
+                user.game_name, user.tag_line
+            );
+            continue;
+        };
+
+        let season = player
+            .queue_skills
+            .get("competitive")
+            .and_then(|skill| skill.seasonal_info_by_season_id.as_ref())
+            .and_then(|seasons| seasons.get(&latest.season_id));
+        let record = match season {
+            Some(season) => format!(
+                ", {}/{} wins",
+                season.number_of_wins, season.number_of_games
+            ),
+            None => String::new(),
+        };
+        let deltas = updates
+            .iter()
+            .map(|update| format!("{:+}", update.ranked_rating_earned))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        println!(
+            "{}#{}: {} {} RR{record} [{deltas}]",
+            user.game_name,
+            user.tag_line,
+            tier_name(latest.tier_after_update),
+            latest.ranked_rating_after_update,
+        );
+
+        let previous = db.get_rank(&user.id).expect("rank should load");
+        if previous
+            .as_ref()
+            .is_some_and(|previous| previous.match_id == latest.match_id)
+        {
+            continue;
+        }
+
+        db.set_rank(&UserRank {
+            user_id: user.id,
+            match_id: latest.match_id.clone(),
+            competitive_tier: latest.tier_after_update,
+            ranked_rating: latest.ranked_rating_after_update,
+            updated_at: Utc::now(),
+        })
+        .expect("rank should be saved");
+
+        // first time the account is seen, there is nothing to compare against
+        let Some(previous) = previous else {
+            continue;
+        };
+
+        let earned = rr_earned_since(&client, &user, &updates, &previous, latest).await;
+
+        let message = generate_rank_message(&user, &previous, latest, earned, act.as_ref());
+        send_webhooks(&client, &webhooks, message).await;
+    }
+}
+
+/// RR earned since `previous` was stored, paging through older competitive
+/// updates until its match shows up. When it is out of reach the difference
+/// in RR is used, counting 100 RR per tier.
+async fn rr_earned_since(
+    client: &ValClient,
+    user: &User,
+    updates: &[CompetitiveUpdate],
+    previous: &UserRank,
+    latest: &CompetitiveUpdate,
+) -> i32 {
+    let (mut earned, mut found) = earned_until(updates, &previous.match_id);
+    let mut last_page = updates.len();
+    let mut start = COMPETITIVE_UPDATES_PAGE;
+    while !found
+        && last_page == COMPETITIVE_UPDATES_PAGE as usize
+        && start < COMPETITIVE_UPDATES_LIMIT
+    {
+        let end = start + COMPETITIVE_UPDATES_PAGE;
+        let page =
+            match mmr::mmr_fetch_competitive_updates(client, user.region, &user.puuid, start, end)
+                .await
+            {
+                Ok(page) => page.matches,
+                Err(err) => {
+                    eprintln!(
+                        "Failed to fetch older competitive updates of {}#{}: {err}",
+                        user.game_name, user.tag_line
+                    );
+                    break;
+                }
+            };
+
+        let (page_earned, page_found) = earned_until(&page, &previous.match_id);
+        earned += page_earned;
+        found = page_found;
+        last_page = page.len();
+        start = end;
+    }
+
+    if found {
+        return earned;
+    }
+    let total = |tier: u32, rr: u32| (tier * 100 + rr) as i32;
+    total(latest.tier_after_update, latest.ranked_rating_after_update)
+        - total(previous.competitive_tier, previous.ranked_rating)
+}
+
+/// RR earned in `updates`, newest first, until `match_id` and whether it was reached.
+fn earned_until(updates: &[CompetitiveUpdate], match_id: &str) -> (i32, bool) {
+    let mut earned = 0;
+    for update in updates {
+        if update.match_id == match_id {
+            return (earned, true);
+        }
+        earned += update.ranked_rating_earned;
+    }
+    (earned, false)
+}
+
+pub fn generate_rank_message(
+    user: &User,
+    previous: &UserRank,
+    latest: &CompetitiveUpdate,
+    earned: i32,
+    act: Option<&Act>,
+) -> WebhookMessage {
+    let (title, color) = if latest.tier_after_update > previous.competitive_tier {
+        (
+            format!("Ranked up to {}", tier_name(latest.tier_after_update)),
+            0x6cc551,
+        )
+    } else if latest.tier_after_update < previous.competitive_tier {
+        (
+            format!("Ranked down to {}", tier_name(latest.tier_after_update)),
+            0xe5534b,
+        )
+    } else if earned >= 0 {
+        (format!("{earned:+} RR"), 0x6cc551)
+    } else {
+        (format!("{earned:+} RR"), 0xe5534b)
+    };
+
+    WebhookMessage {
+        username: Some(format!("{}#{}", user.game_name, user.tag_line)),
+        content: None,
+        embeds: Some(vec![MessageEmbed {
+            title: Some(title),
+            description: Some(format!(
+                "{} {} RR → {} {} RR ({earned:+} RR)",
+                tier_name(previous.competitive_tier),
+                previous.ranked_rating,
+                tier_name(latest.tier_after_update),
+                latest.ranked_rating_after_update,
+            )),
+            color: Some(color),
+            timestamp: DateTime::from_timestamp_millis(latest.match_start_time),
+            image: None,
+            thumbnail: None,
+            footer: act.map(|act| EmbedFooter {
+                text: Some(act.full_name()),
+                icon_url: None,
+                proxy_icon_url: None,
+            }),
+        }]),
+    }
+}
+
+pub async fn loadout(db: &Datastore, client: &ValClient) {
+    refresh_expired_accounts(db, client, io::stdin().is_terminal()).await;
+
+    // items missing from a catalog are shown by their ids
+    let skins: HashMap<String, String> =
+        catalog_or_empty("skin", thirdparty::valdata::get_weapon_skins(client).await)
+            .into_iter()
+            .filter_map(|skin| Some((skin.uuid.to_lowercase(), skin.display_name?)))
+            .collect();
+    let names: HashMap<String, String> = {
+        let weapons = catalog_or_empty("weapon", thirdparty::valdata::get_weapons(client).await);
+        let sprays = catalog_or_empty("spray", thirdparty::valdata::get_sprays(client).await);
+        let cards = catalog_or_empty("card", thirdparty::valdata::get_player_cards(client).await);
+        let titles = catalog_or_empty(
+            "title",
+            thirdparty::valdata::get_player_titles(client).await,
+        );
+
+        weapons
+            .into_iter()
+            .chain(sprays)
+            .chain(cards)
+            .chain(titles)
+            .filter_map(|asset| Some((asset.uuid.to_lowercase(), asset.display_name?)))
+            .collect()
+    };
+    let client = with_client_version(client).await;
+
+    let users = db.get_users().expect("users");
+    for user in users {
+        let Some(session) = usable_session(&user) else {
+            continue;
+        };
+
+        let client = client.with_tokens(&session.access_token, &user.entitlements_token);
+        let loadout =
+            match personalization::personalization_fetch_loadout(&client, user.region, &user.puuid)
+                .await
+            {
+                Ok(loadout) => loadout,
+                Err(err) => {
+                    eprintln!(
+                        "Failed to fetch loadout of {}#{}: {err}",
+                        user.game_name, user.tag_line
+                    );
+                    continue;
+                }
+            };
+
+        println!("{}#{}", user.game_name, user.tag_line);
+        println!(
+            "  Card: {}, Title: {}",
+            resolve_name(&names, &loadout.identity.player_card_id),
+            resolve_name(&names, &loadout.identity.player_title_id),
+        );
+
+        let mut guns: Vec<_> = loadout
+            .guns
+            .iter()
+            .map(|gun| {
+                (
+                    resolve_name(&names, &gun.id),
+                    resolve_name(&skins, &gun.skin_id),
+                )
+            })
+            .collect();
+        guns.sort();
+        for (weapon, skin) in guns {
+            println!("  {weapon}: {skin}");
+        }
+
+        let sprays: Vec<_> = loadout
+            .sprays
+            .iter()
+            .map(|spray| resolve_name(&names, &spray.spray_id))
+            .collect();
+        println!("  Sprays: {}", sprays.join(", "));
+    }
+}
+
+fn resolve_name(names: &HashMap<String, String>, id: &str) -> String {
+    names
+        .get(&id.to_lowercase())
+        .cloned()
+        .unwrap_or(id.to_string())
+}
+
+pub async fn current_act(db: &Datastore, client: &ValClient) {
+    refresh_expired_accounts(db, client, io::stdin().is_terminal()).await;
+
+    let client = with_client_version(client).await;
+    let users = db.get_users().expect("users");
+
+    match load_current_act(&client, &users).await {
+        Some(act) => println!(
+            "{} ends {} ({} days left)",
+            act.full_name(),
+            act.end_time.format("%Y-%m-%d %H:%M"),
+            (act.end_time - Utc::now()).num_days()
+        ),
+        None => println!("No act is currently running"),
+    }
+}
+
+/// Act running now, fetched with the session of the first logged account not waiting for a login.
+pub async fn load_current_act(client: &ValClient, users: &[User]) -> Option<Act> {
+    let (user, session) = users
+        .iter()
+        .filter(|user| !user.needs_reauth)
+        .find_map(|user| user.session.as_ref().map(|session| (user, session)))?;
+    let client = client.with_tokens(&session.access_token, &user.entitlements_token);
+
+    match content::content_fetch_seasons(&client, user.region).await {
+        Ok(seasons) => {
+            if let Some(err) = seasons.content_error {
+                eprintln!(
+                    "Failed to fetch seasons from the content service, using valorant-api: {err}"
+                );
+            }
+            season::current_act(&seasons.seasons)
+        }
+        Err(err) => {
+            eprintln!("Failed to fetch seasons: {err}");
+            None
+        }
+    }
+}
@@ -1,34 +1,97 @@
-use std::{collections::HashMap, ops::Not};
+use std::{
+    collections::HashMap,
+    io::{self, IsTerminal},
+    ops::Not,
+};
 
-use chrono::{Duration, Utc};
-use directories::ProjectDirs;
+use chrono::{DateTime, Duration, Utc};
+use cookie_store::CookieStore;
 use val_api::{
+    client::ValClient,
+    currency::Currency,
     endpoints::{
         self,
-        auth::silent_login,
+        auth::{session_cookie_expires_at, silent_login, SilentLoginOutcome},
         user::{get_entitlements_token, get_region, get_user_info},
     },
-    models::{EmbedImage, MessageEmbed, SkinData, SkinDetails, WebhookMessage},
+    error::ValApiError,
+    item_type::ItemType,
+    jwt,
+    models::{
+        AccessoryStoreOffer, AssetDetails, Bundle, EmbedFooter, EmbedImage, MessageEmbed,
+        PriceDetail, SkinData, SkinDetails, Tokens, Wallet, WebhookMessage,
+    },
+    season::Act,
     thirdparty::{self, discord::send_webhook},
 };
-use val_login_webview2::{login_popup, RIOT_AUTH_PAGE};
 
 use crate::{
+    accounts::handle_account_command,
     datastore::Datastore,
-    models::{AddUser, AddUserSession, User},
+    login::{
+        confirm, ImportedCookiesLogin, InteractiveLogin, LoginProvider, PasswordLogin,
+        PastedRedirectLogin,
+    },
+    models::{AddUser, AddUserSession, User, UserSession},
+    val::load_current_act,
     ValStoreCommands,
 };
 
-pub async fn handle_val_store_command(command: &ValStoreCommands) {
+pub async fn handle_val_store_command(command: &ValStoreCommands, client: &ValClient) {
     let datastore = Datastore::initialize().expect("database should initialize");
+    let client = client.clone();
 
     match command {
-        ValStoreCommands::Add => drop(add_account(&datastore).await),
+        ValStoreCommands::Add {
+            username,
+            paste,
+            cookies,
+        } => {
+            let added = match (username, cookies) {
+                (Some(username), _) => {
+                    let provider = PasswordLogin {
+                        username: username.clone(),
+                    };
+                    add_account(&datastore, &client, &provider).await
+                }
+                (None, Some(path)) => {
+                    let provider = ImportedCookiesLogin { path: path.clone() };
+                    add_account(&datastore, &client, &provider).await
+                }
+                (None, None) if *paste => {
+                    add_account(&datastore, &client, &PastedRedirectLogin::default()).await
+                }
+                (None, None) => {
+                    add_account(&datastore, &client, &InteractiveLogin::default()).await
+                }
+            };
+            if let Err(err) = added {
+                eprintln!("Failed to add account: {err}");
+            }
+        }
         ValStoreCommands::Check {
             force,
             force_nightmarket,
-        } => check(&datastore, force, force_nightmarket).await,
+            reauth_alert_days,
+            non_interactive,
+        } => {
+            let interactive = !non_interactive && io::stdin().is_terminal();
+            check(
+                &datastore,
+                &client,
+                force,
+                force_nightmarket,
+                reauth_alert_days,
+                interactive,
+            )
+            .await
+        }
+        ValStoreCommands::Sessions => sessions(&datastore),
+        ValStoreCommands::Accounts { action } => handle_account_command(&datastore, action),
         ValStoreCommands::Webhook { uri } => webhook(&datastore, uri).await,
+        ValStoreCommands::Wallet => wallet(&datastore, &client).await,
+        ValStoreCommands::Inventory => inventory(&datastore, &client).await,
+        ValStoreCommands::Price { name } => price(&datastore, &client, name).await,
     };
 }
 
@@ -37,9 +100,226 @@ pub async fn webhook(db: &Datastore, uri: &String) {
     println!("Added url as a discord webhook");
 }
 
-pub async fn refresh_expired_accounts(db: &Datastore) {
+pub async fn wallet(db: &Datastore, client: &ValClient) {
+    refresh_expired_accounts(db, client, io::stdin().is_terminal()).await;
+
+    let client = with_client_version(client).await;
+    let users = db.get_users().expect("users");
+
+    for user in users {
+        let Some(session) = usable_session(&user) else {
+            continue;
+        };
+
+        let client = client.with_tokens(&session.access_token, &user.entitlements_token);
+        match endpoints::store::store_fetch_wallet(&client, user.region, &user.puuid).await {
+            Ok(wallet) => println!(
+                "{}#{}: {}",
+                user.game_name,
+                user.tag_line,
+                Currency::KNOWN
+                    .iter()
+                    .map(|currency| format!("{} {currency}", wallet.balance(currency)))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            Err(err) => eprintln!(
+                "Failed to fetch wallet of {}#{}: {err}",
+                user.game_name, user.tag_line
+            ),
+        }
+    }
+}
+
+pub async fn price(db: &Datastore, client: &ValClient, name: &str) {
+    refresh_expired_accounts(db, client, io::stdin().is_terminal()).await;
+
+    let client = with_client_version(client).await;
+    let users = db.get_users().expect("users");
+    let Some((user, session)) = users
+        .iter()
+        .filter(|user| !user.needs_reauth)
+        .find_map(|user| user.session.as_ref().map(|session| (user, session)))
+    else {
+        eprintln!("No logged account available to fetch offers");
+        return;
+    };
+
+    let client = client.with_tokens(&session.access_token, &user.entitlements_token);
+    let catalog = match endpoints::store::store_fetch_offers(&client, user.region).await {
+        Ok(catalog) => catalog,
+        Err(err) => {
+            eprintln!("Failed to fetch offers: {err}");
+            return;
+        }
+    };
+
+    let skins = match thirdparty::valdata::get_weapon_skins(&client).await {
+        Ok(skins) => skins,
+        Err(err) => {
+            eprintln!("Failed to load skin catalog: {err}");
+            return;
+        }
+    };
+
+    let name = name.to_lowercase();
+    for skin in skins {
+        let Some(display_name) = &skin.display_name else {
+            continue;
+        };
+        if !display_name.to_lowercase().contains(&name) {
+            continue;
+        }
+
+        match skin
+            .levels
+            .first()
+            .and_then(|level| catalog.find_by_item(&level.uuid))
+        {
+            Some(offer) => println!("{display_name}: {}", format_price(&offer.cost)),
+            None => println!("{display_name}: not for sale"),
+        }
+    }
+}
+
+pub async fn inventory(db: &Datastore, client: &ValClient) {
+    refresh_expired_accounts(db, client, io::stdin().is_terminal()).await;
+
+    let Some(skins) = load_skins(client).await else {
+        return;
+    };
+    let client = with_client_version(client).await;
+    let users = db.get_users().expect("users");
+
+    let mut catalog = None;
+    for user in users {
+        let Some(session) = usable_session(&user) else {
+            continue;
+        };
+
+        let client = client.with_tokens(&session.access_token, &user.entitlements_token);
+        if catalog.is_none() {
+            match endpoints::store::store_fetch_offers(&client, user.region).await {
+                Ok(offers) => catalog = Some(offers),
+                Err(err) => eprintln!("Failed to fetch offers: {err}"),
+            }
+        }
+
+        let owned = match endpoints::store::store_fetch_entitlements(
+            &client,
+            user.region,
+            &user.puuid,
+            &ItemType::SkinLevel,
+        )
+        .await
+        {
+            Ok(owned) => owned,
+            Err(err) => {
+                eprintln!(
+                    "Failed to fetch inventory of {}#{}: {err}",
+                    user.game_name, user.tag_line
+                );
+                continue;
+            }
+        };
+
+        // only the first level of a skin is purchasable, upgrades are not counted
+        let mut total = 0;
+        let mut names: Vec<_> = owned
+            .entitlements
+            .iter()
+            .filter_map(|entitlement| {
+                let skin = skins.get(&entitlement.item_id)?;
+                total += catalog
+                    .as_ref()
+                    .and_then(|catalog| catalog.find_by_item(&entitlement.item_id))
+                    .and_then(|offer| offer.cost.valorant_points())
+                    .unwrap_or_default();
+                Some(skin.display_name.clone().unwrap_or(skin.uuid.clone()))
+            })
+            .collect();
+        names.sort();
+
+        println!(
+            "{}#{}: {} skins, {total} VP",
+            user.game_name,
+            user.tag_line,
+            names.len()
+        );
+        for name in names {
+            println!("  {name}");
+        }
+    }
+}
+
+/// Skin catalog indexed by the uuid of the first level, which is also the offer id.
+async fn load_skins(client: &ValClient) -> Option<HashMap<String, SkinDetails>> {
+    match thirdparty::valdata::get_weapon_skins(client).await {
+        Ok(skins) => Some(
+            skins
+                .into_iter()
+                .filter(|detail| !detail.levels.is_empty())
+                .map(|detail| (detail.levels[0].uuid.clone(), detail))
+                .collect(),
+        ),
+        Err(err) => {
+            eprintln!("Failed to load skin catalog: {err}");
+            None
+        }
+    }
+}
+
+/// Offers missing from the catalog are skipped instead of failing the whole store.
+fn skin_detail(skins: &HashMap<String, SkinDetails>, offer_id: &str) -> Option<SkinDetails> {
+    let detail = skins.get(offer_id).cloned();
+    if detail.is_none() {
+        eprintln!("Skipping offer {offer_id}, missing from the skin catalog");
+    }
+    detail
+}
+
+fn format_price(price: &PriceDetail) -> String {
+    price
+        .iter()
+        .map(|(currency, amount)| format!("{amount} {currency}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub async fn with_client_version(client: &ValClient) -> ValClient {
+    let mut client = client.clone();
+    match thirdparty::valdata::get_client_version(&client).await {
+        Ok(version) => client.set_client_version(version),
+        Err(err) => eprintln!("Failed to load client version: {err}"),
+    }
+    client
+}
+
+/// Session to use for `user`, `None` with the reason printed when the
+/// account is not logged or waits for a new login.
+pub fn usable_session(user: &User) -> Option<&UserSession> {
+    let Some(session) = &user.session else {
+        println!("User {}#{} is not logged", user.game_name, user.tag_line);
+        return None;
+    };
+    if user.needs_reauth {
+        println!(
+            "Skipping user {}#{}, login required",
+            user.game_name, user.tag_line
+        );
+        return None;
+    }
+
+    Some(session)
+}
+
+/// Refreshes expired sessions, accounts that need a new login get a login
+/// when `interactive` and are flagged and reported to the webhooks otherwise.
+pub async fn refresh_expired_accounts(db: &Datastore, client: &ValClient, interactive: bool) {
     let users = db.get_users().expect("users");
 
+    // the entitlements token is refreshed together with the session so both
+    // are valid for as long as the session is
     let (not_logged, expired): (Vec<_>, Vec<_>) = users
         .into_iter()
         .filter(|user| {
@@ -47,45 +327,188 @@ pub async fn refresh_expired_accounts(db: &Datastore) {
                 .as_ref()
                 .is_some_and(|s| s.expires_at >= Utc::now())
                 .not()
+                || user.entitlements_expires_at < Utc::now()
         })
         .partition(|user| user.session.as_ref().is_none());
 
     let mut failed_refresh = Vec::new();
 
     for user in expired {
-        let cookies = user.session.as_ref().unwrap().authorized_cookies.clone();
-        let Some((tokens, cookies)) = silent_login(&cookies).await else {
-            failed_refresh.push(user);
-            continue;
+        let cookies = &user.session.as_ref().unwrap().authorized_cookies;
+        let (tokens, cookies) = match silent_login(client, cookies).await {
+            Ok(SilentLoginOutcome::LoggedIn { tokens, cookies }) => (tokens, cookies),
+            Ok(SilentLoginOutcome::ReauthRequired) => {
+                failed_refresh.push(user);
+                continue;
+            }
+            Ok(SilentLoginOutcome::AuthError { code, description }) => {
+                eprintln!(
+                    "Riot refused to refresh session of {}#{}: {code} {}",
+                    user.game_name,
+                    user.tag_line,
+                    description.unwrap_or_default()
+                );
+                continue;
+            }
+            Ok(SilentLoginOutcome::UnexpectedRedirect { location }) => {
+                eprintln!(
+                    "Unexpected answer refreshing session of {}#{}: redirect to {}",
+                    user.game_name,
+                    user.tag_line,
+                    location.as_deref().unwrap_or("nowhere")
+                );
+                continue;
+            }
+            Err(err) => {
+                eprintln!(
+                    "Failed to refresh session of {}#{}: {err}",
+                    user.game_name, user.tag_line
+                );
+                continue;
+            }
         };
 
+        // store the rotated cookies first, they may not survive another login
         db.set_session(&AddUserSession {
             user_id: user.id,
-            access_token: tokens.access_token,
-            id_token: tokens.id_token,
-            expires_at: Utc::now() + Duration::seconds(tokens.expires_in as i64),
+            expires_at: tokens.expires_at(),
+            access_token: tokens.access_token.clone(),
+            id_token: tokens.id_token.clone(),
             authorized_cookies: cookies,
         })
-        .expect("session should be updated")
+        .expect("session should be updated");
+        if user.needs_reauth {
+            db.set_user_needs_reauth(&user.id, false)
+                .expect("reauth flag should be cleared");
+        }
+
+        let mut client = client.clone();
+        client.set_access_token(&tokens.access_token);
+        let entitlements_token = match get_entitlements_token(&client).await {
+            Ok(token) => token,
+            Err(err) => {
+                eprintln!(
+                    "Failed to refresh entitlements of {}#{}: {err}",
+                    user.game_name, user.tag_line
+                );
+                continue;
+            }
+        };
+        db.set_user_entitlements_token(
+            &user.id,
+            &entitlements_token,
+            &entitlements_expires_at(&entitlements_token, &tokens),
+        )
+        .expect("entitlements token should be updated");
+    }
+
+    if !interactive {
+        let webhooks = db.get_webhooks().expect("webhooks");
+        for user in not_logged.into_iter().chain(failed_refresh) {
+            println!(
+                "Login required for {}#{}, skipping",
+                user.game_name, user.tag_line
+            );
+            // report once, the flag is cleared by the next successful login
+            if user.needs_reauth {
+                continue;
+            }
+            db.set_user_needs_reauth(&user.id, true)
+                .expect("reauth flag should be set");
+            send_webhooks(client, &webhooks, generate_login_required_message(&user)).await;
+        }
+        return;
     }
 
     for user in not_logged.into_iter().chain(failed_refresh) {
-        println!("Login for {}#{}", user.game_name, user.tag_line);
-        let user_id = add_account(&db).await;
-        if user_id != user.id {
-            eprintln!("Logged in on the wrong account!");
+        match relogin_account(db, client, &user, &InteractiveLogin::default()).await {
+            Ok(true) => continue,
+            Ok(false) => println!("Skipped login for {}#{}", user.game_name, user.tag_line),
+            Err(err) => eprintln!("Failed to login: {err}"),
+        }
+        // keeps the stale session out of the rest of the run
+        db.set_user_needs_reauth(&user.id, true)
+            .expect("reauth flag should be set");
+    }
+}
+
+/// Logs in with `provider` and stores the account, `None` when the login failed.
+pub async fn add_account(
+    db: &Datastore,
+    client: &ValClient,
+    provider: &impl LoginProvider,
+) -> Result<Option<i64>, ValApiError> {
+    let Some((tokens, cookies)) = provider.login(client).await else {
+        return Ok(None);
+    };
+
+    register_account(db, client, tokens, cookies)
+        .await
+        .map(Some)
+}
+
+/// Logs `user` in again, sessions of any other account are discarded
+/// before anything is stored. `false` when the user chose to skip the account.
+pub async fn relogin_account(
+    db: &Datastore,
+    client: &ValClient,
+    user: &User,
+    provider: &impl LoginProvider,
+) -> Result<bool, ValApiError> {
+    let name = format!("{}#{}", user.game_name, user.tag_line);
+    loop {
+        println!("Login required for {name}, log in with that account");
+        let Some((tokens, cookies)) = provider.login(client).await else {
+            if confirm(&format!("Retry login for {name}? [Y/n] ")) {
+                continue;
+            }
+            return Ok(false);
+        };
+
+        let puuid = token_puuid(client, &tokens).await?;
+        if puuid != user.puuid {
+            eprintln!("Logged in on another account than {name}, the session was discarded");
+            // otherwise the provider would log in on the same account again
+            provider.forget_session();
+            if confirm(&format!("Retry login for {name}? [Y/n] ")) {
+                continue;
+            }
+            return Ok(false);
         }
+
+        register_account(db, client, tokens, cookies).await?;
+        return Ok(true);
     }
 }
 
-pub async fn add_account(db: &Datastore) -> i64 {
-    let project = ProjectDirs::from("", "", "riot-cli").expect("project dirs should work");
-    let folder = project.data_dir().join("edge-profile");
+/// Puuid the tokens belong to, from the access token claims or the user info.
+async fn token_puuid(client: &ValClient, tokens: &Tokens) -> Result<String, ValApiError> {
+    if let Some(sub) = jwt::decode_claims(&tokens.access_token)
+        .ok()
+        .and_then(|claims| claims.sub)
+    {
+        return Ok(sub);
+    }
+
+    let mut client = client.clone();
+    client.set_access_token(&tokens.access_token);
+    let (_, user_info) = get_user_info(&client).await?;
+    Ok(user_info.sub)
+}
 
-    let (tokens, cookies) = login_popup(&folder, RIOT_AUTH_PAGE).expect("failed to login");
-    let (raw_user_info, user_info) = get_user_info(&tokens.access_token).await;
-    let region = get_region(&tokens.access_token, tokens.id_token.clone()).await;
-    let entitlements_token = get_entitlements_token(&tokens.access_token).await;
+/// Stores the account behind the tokens along with its session.
+pub async fn register_account(
+    db: &Datastore,
+    client: &ValClient,
+    tokens: Tokens,
+    cookies: CookieStore,
+) -> Result<i64, ValApiError> {
+    let mut client = client.clone();
+    client.set_access_token(&tokens.access_token);
+
+    let (raw_user_info, user_info) = get_user_info(&client).await?;
+    let region = get_region(&client, tokens.id_token.clone()).await?;
+    let entitlements_token = get_entitlements_token(&client).await?;
 
     let user = AddUser {
         puuid: user_info.sub.clone(),
@@ -93,18 +516,22 @@ pub async fn add_account(db: &Datastore) -> i64 {
         tag_line: user_info.acct.tag_line.clone(),
         region: region.affinities.live,
         user_info: raw_user_info,
+        entitlements_expires_at: entitlements_expires_at(&entitlements_token, &tokens),
         entitlements_token,
         next_nightmarket: Utc::now(),
         next_store: Utc::now(),
+        next_bundle: Utc::now(),
+        next_accessory_store: Utc::now(),
     };
 
     let user_id = db.add_user(&user).expect("user should be added");
 
+    let cookies_expire_at = session_cookie_expires_at(&cookies);
     let session = AddUserSession {
         user_id,
         access_token: tokens.access_token.clone(),
         id_token: tokens.id_token.clone(),
-        expires_at: Utc::now() + Duration::seconds(tokens.expires_in as i64),
+        expires_at: tokens.expires_at(),
         authorized_cookies: cookies,
     };
 
@@ -114,46 +541,209 @@ pub async fn add_account(db: &Datastore) -> i64 {
         "Added account {}#{} to database",
         user.game_name, user.tag_line
     );
+    match cookies_expire_at {
+        Some(expires_at) => println!("Login needed again after {expires_at}"),
+        None => {
+            println!("The session cookie has no expiry, login will be needed once it is dropped")
+        }
+    }
 
-    user_id
+    Ok(user_id)
 }
 
-pub async fn check(db: &Datastore, force: &bool, force_nightmarket: &bool) {
-    refresh_expired_accounts(&db).await;
+/// Expiry of an entitlements token, tokens without an `exp` claim are assumed
+/// to last as long as the access token they were issued for.
+fn entitlements_expires_at(token: &str, tokens: &Tokens) -> DateTime<Utc> {
+    jwt::decode_claims(token)
+        .ok()
+        .and_then(|claims| claims.expires_at())
+        .unwrap_or_else(|| tokens.expires_at())
+}
 
-    let hash = thirdparty::valdata::get_weapon_skins().await;
-    let hash: HashMap<String, SkinDetails> = hash
-        .into_iter()
-        .filter(|detail| detail.levels.len() > 0)
-        .map(|detail| (detail.levels[0].uuid.clone(), detail))
-        .collect();
-    let client_ver = thirdparty::valdata::get_client_version().await;
+pub fn sessions(db: &Datastore) {
+    let users = db.get_users().expect("users");
+
+    for user in users {
+        println!("{}#{}", user.game_name, user.tag_line);
+        let Some(session) = &user.session else {
+            println!("  not logged in");
+            continue;
+        };
+
+        println!(
+            "  access token  {}",
+            format_expiry(Some(session.expires_at))
+        );
+        println!(
+            "  entitlements  {}",
+            format_expiry(Some(user.entitlements_expires_at))
+        );
+        println!(
+            "  login cookie  {}",
+            format_expiry(session.cookies_expire_at())
+        );
+    }
+}
+
+fn format_expiry(expires_at: Option<DateTime<Utc>>) -> String {
+    let Some(expires_at) = expires_at else {
+        return "no expiry".to_string();
+    };
+
+    let remaining = expires_at - Utc::now();
+    if remaining <= Duration::zero() {
+        return format!("{expires_at} (expired)");
+    }
+
+    match remaining.num_days() {
+        0 => format!(
+            "{expires_at} (in {}h {}m)",
+            remaining.num_hours(),
+            remaining.num_minutes() % 60
+        ),
+        days => format!("{expires_at} (in {days}d {}h)", remaining.num_hours() % 24),
+    }
+}
+
+/// Warns the webhooks once per cookie expiry when an account will need
+/// an interactive login within `days`.
+pub async fn alert_expiring_sessions(db: &Datastore, client: &ValClient, days: i64) {
+    if days <= 0 {
+        return;
+    }
 
     let users = db.get_users().expect("users");
     let webhooks = db.get_webhooks().expect("webhooks");
 
     for user in users {
         let Some(session) = &user.session else {
-            println!("User {}#{} is not logged", user.game_name, user.tag_line);
             continue;
         };
+        let Some(expires_at) = session.cookies_expire_at() else {
+            continue;
+        };
+        if expires_at - Utc::now() > Duration::days(days)
+            || session.cookie_expiry_alerted == Some(expires_at)
+        {
+            continue;
+        }
+
+        println!(
+            "Login of {}#{} expires at {expires_at}",
+            user.game_name, user.tag_line
+        );
+        send_webhooks(client, &webhooks, generate_reauth_alert(&user, expires_at)).await;
+        db.set_cookie_expiry_alerted(&user.id, &expires_at)
+            .expect("alert should be recorded");
+    }
+}
 
-        if user.next_store > Utc::now() && !force && !force_nightmarket {
+fn generate_login_required_message(user: &User) -> WebhookMessage {
+    WebhookMessage {
+        username: Some(format!("{}#{}", user.game_name, user.tag_line)),
+        content: None,
+        embeds: Some(vec![MessageEmbed {
+            title: Some(format!(
+                "Login required for {}#{}",
+                user.game_name, user.tag_line
+            )),
+            description: Some(
+                "The session expired and could not be refreshed, run `riot-cli val-store add` to log in again.".to_string(),
+            ),
+            color: Some(0xe5534b),
+            timestamp: Some(Utc::now()),
+            image: None,
+            thumbnail: None,
+            footer: None,
+        }]),
+    }
+}
+
+fn generate_reauth_alert(user: &User, expires_at: DateTime<Utc>) -> WebhookMessage {
+    WebhookMessage {
+        username: Some(format!("{}#{}", user.game_name, user.tag_line)),
+        content: None,
+        embeds: Some(vec![MessageEmbed {
+            title: Some("Login required soon".to_string()),
+            description: Some(format!(
+                "The saved login expires <t:{}:R>, run `riot-cli val-store add` before then to keep the checks running.",
+                expires_at.timestamp()
+            )),
+            color: Some(0xf0b232),
+            timestamp: Some(expires_at),
+            image: None,
+            thumbnail: None,
+            footer: None,
+        }]),
+    }
+}
+
+pub async fn check(
+    db: &Datastore,
+    client: &ValClient,
+    force: &bool,
+    force_nightmarket: &bool,
+    reauth_alert_days: &i64,
+    interactive: bool,
+) {
+    refresh_expired_accounts(db, client, interactive).await;
+    alert_expiring_sessions(db, client, *reauth_alert_days).await;
+
+    let Some(hash) = load_skins(client).await else {
+        return;
+    };
+    // asset catalogs are only needed for bundle and accessory embeds
+    let mut bundle_assets = None;
+    let mut accessory_assets = None;
+    let client = with_client_version(client).await;
+
+    let users = db.get_users().expect("users");
+    let webhooks = db.get_webhooks().expect("webhooks");
+    let act = load_current_act(&client, &users).await;
+
+    for user in users {
+        let Some(session) = usable_session(&user) else {
+            continue;
+        };
+
+        // daily offers, bundles and accessories rotate on their own schedules
+        let next_rotation = user
+            .next_store
+            .min(user.next_bundle)
+            .min(user.next_accessory_store);
+        if next_rotation > Utc::now() && !force && !force_nightmarket {
             println!(
-                "Skipping user {}#{}, next shop at {}",
-                user.game_name, user.tag_line, user.next_store
+                "Skipping user {}#{}, next shop at {next_rotation}",
+                user.game_name, user.tag_line
             );
             continue;
         }
 
-        let store = endpoints::store::store_fetch_storefront(
-            &client_ver,
-            &user.entitlements_token,
-            &session.access_token,
-            &user.region,
-            &user.puuid,
-        )
-        .await;
+        let client = client.with_tokens(&session.access_token, &user.entitlements_token);
+        let store =
+            match endpoints::store::store_fetch_storefront(&client, user.region, &user.puuid).await
+            {
+                Ok(store) => store,
+                Err(err) => {
+                    eprintln!(
+                        "Failed to fetch store of {}#{}: {err}",
+                        user.game_name, user.tag_line
+                    );
+                    continue;
+                }
+            };
+
+        let wallet =
+            match endpoints::store::store_fetch_wallet(&client, user.region, &user.puuid).await {
+                Ok(wallet) => Some(wallet),
+                Err(err) => {
+                    eprintln!(
+                        "Failed to fetch wallet of {}#{}: {err}",
+                        user.game_name, user.tag_line
+                    );
+                    None
+                }
+            };
 
         if let Some(bonus) = store.bonus_store {
             if user.next_nightmarket <= Utc::now() || *force_nightmarket {
@@ -165,15 +755,27 @@ pub async fn check(db: &Datastore, force: &bool, force_nightmarket: &bool) {
                 let skins: Vec<_> = bonus
                     .bonus_store_offers
                     .iter()
-                    .map(|bonus_offer| SkinData {
-                        offer: bonus_offer.offer.clone(),
-                        detail: hash.get(&bonus_offer.offer.offer_id).unwrap().clone(),
-                        bonus_offer: Some(bonus_offer.clone()),
+                    .filter_map(|bonus_offer| {
+                        Some(SkinData {
+                            offer: bonus_offer.offer.clone(),
+                            detail: skin_detail(&hash, &bonus_offer.offer.offer_id)?,
+                            bonus_offer: Some(bonus_offer.clone()),
+                        })
                     })
                     .collect();
 
-                let message = generate_store_messages(&user, skins);
-                send_webhooks(&webhooks, message).await;
+                let mut message =
+                    generate_store_messages(&user, skins, wallet.as_ref(), act.as_ref());
+                message.content = Some(match &act {
+                    Some(act) => format!(
+                        "Night market ends <t:{}:R>, {} ends <t:{}:R>",
+                        next_store.timestamp(),
+                        act.full_name(),
+                        act.end_time.timestamp()
+                    ),
+                    None => format!("Night market ends <t:{}:R>", next_store.timestamp()),
+                });
+                send_webhooks(&client, &webhooks, message).await;
 
                 println!("Sent nightmarket of {}#{}", user.game_name, user.tag_line);
             }
@@ -195,22 +797,269 @@ pub async fn check(db: &Datastore, force: &bool, force_nightmarket: &bool) {
                 .skins_panel_layout
                 .single_item_store_offers
                 .iter()
-                .map(|offer| SkinData {
-                    offer: offer.clone(),
-                    detail: hash.get(&offer.offer_id).unwrap().clone(),
-                    bonus_offer: None,
+                .filter_map(|offer| {
+                    Some(SkinData {
+                        offer: offer.clone(),
+                        detail: skin_detail(&hash, &offer.offer_id)?,
+                        bonus_offer: None,
+                    })
                 })
                 .collect();
 
-            let message = generate_store_messages(&user, skins);
-            send_webhooks(&webhooks, message).await;
+            let message = generate_store_messages(&user, skins, wallet.as_ref(), act.as_ref());
+            send_webhooks(&client, &webhooks, message).await;
 
             println!("Sent store of user {}#{}", user.game_name, user.tag_line);
         }
+
+        if let Some(featured) = &store.featured_bundle {
+            if user.next_bundle <= Utc::now() || *force {
+                let duration = Duration::seconds(featured.bundle_remaining_duration_in_seconds);
+                let next_bundle = Utc::now() + duration;
+                db.set_user_next_bundle(&user.id, &next_bundle)
+                    .expect("failed update next_bundle");
+
+                let bundles = if featured.bundles.is_empty() {
+                    std::slice::from_ref(&featured.bundle)
+                } else {
+                    featured.bundles.as_slice()
+                };
+
+                if bundle_assets.is_none() {
+                    bundle_assets = Some(load_bundle_assets(&client).await);
+                }
+                let message = generate_bundle_messages(
+                    &user,
+                    bundles,
+                    bundle_assets.as_ref().unwrap(),
+                    wallet.as_ref(),
+                    act.as_ref(),
+                );
+                send_webhooks(&client, &webhooks, message).await;
+
+                println!("Sent bundles of user {}#{}", user.game_name, user.tag_line);
+            }
+        }
+
+        if let Some(accessory) = &store.accessory_store {
+            if user.next_accessory_store <= Utc::now() || *force {
+                let duration =
+                    Duration::seconds(accessory.accessory_store_remaining_duration_in_seconds);
+                let next_accessory_store = Utc::now() + duration;
+                db.set_user_next_accessory_store(&user.id, &next_accessory_store)
+                    .expect("failed update next_accessory_store");
+
+                if accessory_assets.is_none() {
+                    accessory_assets = Some(load_accessory_assets(&client).await);
+                }
+                let message = generate_accessory_messages(
+                    &user,
+                    &accessory.accessory_store_offers,
+                    accessory_assets.as_ref().unwrap(),
+                    wallet.as_ref(),
+                    act.as_ref(),
+                );
+                send_webhooks(&client, &webhooks, message).await;
+
+                println!(
+                    "Sent accessory store of user {}#{}",
+                    user.game_name, user.tag_line
+                );
+            }
+        }
     }
 }
 
-pub fn generate_store_messages(user: &User, skins: Vec<SkinData>) -> WebhookMessage {
+async fn load_bundle_assets(client: &ValClient) -> HashMap<String, AssetDetails> {
+    catalog_or_empty("bundle", thirdparty::valdata::get_bundles(client).await)
+        .into_iter()
+        .map(|bundle| (bundle.uuid.clone(), bundle))
+        .collect()
+}
+
+async fn load_accessory_assets(client: &ValClient) -> HashMap<String, AssetDetails> {
+    let sprays = catalog_or_empty("spray", thirdparty::valdata::get_sprays(client).await);
+    let cards = catalog_or_empty("card", thirdparty::valdata::get_player_cards(client).await);
+    let titles = catalog_or_empty(
+        "title",
+        thirdparty::valdata::get_player_titles(client).await,
+    );
+    let buddies = catalog_or_empty("buddy", thirdparty::valdata::get_buddies(client).await);
+
+    // buddies are sold by level, but only the buddy itself has a useful name
+    let buddy_levels = buddies.into_iter().flat_map(|buddy| {
+        buddy
+            .levels
+            .into_iter()
+            .map(|level| AssetDetails {
+                uuid: level.uuid,
+                display_name: buddy.display_name.clone(),
+                display_icon: level.display_icon.or(buddy.display_icon.clone()),
+            })
+            .collect::<Vec<_>>()
+    });
+
+    sprays
+        .into_iter()
+        .chain(cards)
+        .chain(titles)
+        .chain(buddy_levels)
+        .map(|asset| (asset.uuid.clone(), asset))
+        .collect()
+}
+
+/// Reports a catalog that failed to load and continues without its names and images.
+pub fn catalog_or_empty<T>(name: &str, catalog: Result<Vec<T>, ValApiError>) -> Vec<T> {
+    catalog.unwrap_or_else(|err| {
+        eprintln!("Failed to load {name} catalog: {err}");
+        Vec::new()
+    })
+}
+
+/// Footer with the act the offer was seen in and whether the wallet covers it.
+fn store_footer(
+    wallet: Option<&Wallet>,
+    cost: &PriceDetail,
+    act: Option<&Act>,
+) -> Option<EmbedFooter> {
+    let affordable = wallet.map(|wallet| {
+        if wallet.can_afford(cost) {
+            "Affordable".to_string()
+        } else {
+            "Not affordable".to_string()
+        }
+    });
+    let parts: Vec<_> = act
+        .map(Act::full_name)
+        .into_iter()
+        .chain(affordable)
+        .collect();
+
+    if parts.is_empty() {
+        return None;
+    }
+
+    Some(EmbedFooter {
+        text: Some(parts.join(" · ")),
+        icon_url: None,
+        proxy_icon_url: None,
+    })
+}
+
+fn currency_icon(currency: &Currency) -> String {
+    match currency {
+        Currency::ValorantPoints => "<:vp:1274118602001350757>".to_string(),
+        currency => currency.to_string(),
+    }
+}
+
+pub fn generate_bundle_messages(
+    user: &User,
+    bundles: &[Bundle],
+    assets: &HashMap<String, AssetDetails>,
+    wallet: Option<&Wallet>,
+    act: Option<&Act>,
+) -> WebhookMessage {
+    WebhookMessage {
+        username: Some(format!("{}#{}", user.game_name, user.tag_line)),
+        content: None,
+        embeds: Some(
+            bundles
+                .iter()
+                .map(|bundle| {
+                    let asset = assets.get(&bundle.data_asset_id);
+                    let base_cost = bundle.base_cost();
+                    let discounted_cost = bundle.discounted_cost();
+                    let icon = currency_icon(&bundle.currency_id);
+
+                    MessageEmbed {
+                        title: asset.and_then(|asset| asset.display_name.clone()),
+                        description: Some(if discounted_cost < base_cost {
+                            format!("{icon} ~~{base_cost}~~ {discounted_cost}")
+                        } else {
+                            format!("{icon} {base_cost}")
+                        }),
+                        color: Some(0x1e90ff),
+                        timestamp: None,
+                        image: asset
+                            .and_then(|asset| asset.display_icon.clone())
+                            .map(|url| EmbedImage { url }),
+                        thumbnail: None,
+                        footer: {
+                            let mut cost = PriceDetail::default();
+                            cost.amounts
+                                .insert(bundle.currency_id.clone(), discounted_cost);
+                            store_footer(wallet, &cost, act)
+                        },
+                    }
+                })
+                .collect(),
+        ),
+    }
+}
+
+pub fn generate_accessory_messages(
+    user: &User,
+    offers: &[AccessoryStoreOffer],
+    assets: &HashMap<String, AssetDetails>,
+    wallet: Option<&Wallet>,
+    act: Option<&Act>,
+) -> WebhookMessage {
+    WebhookMessage {
+        username: Some(format!("{}#{}", user.game_name, user.tag_line)),
+        content: None,
+        embeds: Some(
+            offers
+                .iter()
+                .map(|accessory| {
+                    let reward_assets: Vec<_> = accessory
+                        .offer
+                        .rewards
+                        .iter()
+                        .filter_map(|reward| assets.get(&reward.item_id))
+                        .collect();
+
+                    MessageEmbed {
+                        title: Some(
+                            reward_assets
+                                .iter()
+                                .filter_map(|asset| asset.display_name.clone())
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                        )
+                        .filter(|title| !title.is_empty()),
+                        description: Some(
+                            accessory
+                                .offer
+                                .cost
+                                .iter()
+                                .map(|(currency, amount)| {
+                                    format!("{} {amount}", currency_icon(currency))
+                                })
+                                .collect::<Vec<_>>()
+                                .join(" "),
+                        ),
+                        color: Some(0xf5c542),
+                        timestamp: None,
+                        image: None,
+                        thumbnail: reward_assets
+                            .iter()
+                            .find_map(|asset| asset.display_icon.clone())
+                            .map(|url| EmbedImage { url }),
+                        footer: store_footer(wallet, &accessory.offer.cost, act),
+                    }
+                })
+                .collect(),
+        ),
+    }
+}
+
+pub fn generate_store_messages(
+    user: &User,
+    skins: Vec<SkinData>,
+    wallet: Option<&Wallet>,
+    act: Option<&Act>,
+) -> WebhookMessage {
     WebhookMessage {
         username: Some(format!("{}#{}", user.game_name, user.tag_line)),
         content: None,
@@ -222,13 +1071,16 @@ pub fn generate_store_messages(user: &User, skins: Vec<SkinData>) -> WebhookMess
                     description: match &skin.bonus_offer {
                         Some(bonus_offer) => Some(format!(
                             "<:vp:1274118602001350757> ~~{}~~ {} (-{}%)",
-                            skin.offer.cost.valorant_points,
-                            bonus_offer.discount_costs.valorant_points,
+                            skin.offer.cost.valorant_points().unwrap_or_default(),
+                            bonus_offer
+                                .discount_costs
+                                .valorant_points()
+                                .unwrap_or_default(),
                             bonus_offer.discount_percent,
                         )),
                         None => Some(format!(
                             "<:vp:1274118602001350757> {}",
-                            skin.offer.cost.valorant_points,
+                            skin.offer.cost.valorant_points().unwrap_or_default(),
                         )),
                     },
                     image: None,
@@ -247,15 +1099,23 @@ pub fn generate_store_messages(user: &User, skins: Vec<SkinData>) -> WebhookMess
                         0xff00aa
                     }),
                     timestamp: None,
-                    footer: None,
+                    footer: {
+                        let cost = match &skin.bonus_offer {
+                            Some(bonus_offer) => &bonus_offer.discount_costs,
+                            None => &skin.offer.cost,
+                        };
+                        store_footer(wallet, cost, act)
+                    },
                 })
                 .collect(),
         ),
     }
 }
 
-pub async fn send_webhooks(webhooks: &Vec<String>, message: WebhookMessage) {
+pub async fn send_webhooks(client: &ValClient, webhooks: &Vec<String>, message: WebhookMessage) {
     for webhook in webhooks {
-        send_webhook(webhook, &message).await;
+        if let Err(err) = send_webhook(client, webhook, &message).await {
+            eprintln!("Failed to send webhook: {err}");
+        }
     }
 }
@@ -0,0 +1,351 @@
+use std::sync::{Arc, OnceLock};
+
+use reqwest::{header, redirect::Policy, RequestBuilder, Response};
+use rustls::{
+    crypto::{
+        aws_lc_rs::{
+            self,
+            cipher_suite::{
+                TLS13_AES_128_GCM_SHA256, TLS13_AES_256_GCM_SHA384, TLS13_CHACHA20_POLY1305_SHA256,
+            },
+        },
+        CryptoProvider,
+    },
+    version::TLS13,
+    ClientConfig,
+};
+use rustls_native_certs::load_native_certs;
+
+use crate::{
+    error::{self, Result, ValApiError},
+    region::Region,
+    retry::RetryPolicy,
+};
+
+pub const DEFAULT_PLATFORM: &str = "ew0KCSJwbGF0Zm9ybVR5cGUiOiAiUEMiLA0KCSJwbGF0Zm9ybU9TIjogIldpbmRvd3MiLA0KCSJwbGF0Zm9ybU9TVmVyc2lvbiI6ICIxMC4wLjE5MDQyLjEuMjU2LjY0Yml0IiwNCgkicGxhdGZvcm1DaGlwc2V0IjogIlVua25vd24iDQp9";
+
+/// Hosts used by the endpoint builders.
+///
+/// Shard dependent hosts are templates where `{shard}` and `{region}` get
+/// replaced for the account, so the whole table can point to a local server.
+#[derive(Debug, Clone)]
+pub struct BaseUrls {
+    pub auth: String,
+    pub entitlements: String,
+    pub geo: String,
+    pub pd: String,
+    pub glz: String,
+    pub shared: String,
+    pub valorant_api: String,
+}
+
+impl Default for BaseUrls {
+    fn default() -> Self {
+        Self {
+            auth: "https://auth.riotgames.com".to_string(),
+            entitlements: "https://entitlements.auth.riotgames.com".to_string(),
+            geo: "https://riot-geo.pas.si.riotgames.com".to_string(),
+            pd: "https://pd.{shard}.a.pvp.net".to_string(),
+            glz: "https://glz-{region}-1.{shard}.a.pvp.net".to_string(),
+            shared: "https://shared.{shard}.a.pvp.net".to_string(),
+            valorant_api: "https://valorant-api.com".to_string(),
+        }
+    }
+}
+
+impl BaseUrls {
+    /// Every host replaced by `base`, useful to target a mock server.
+    pub fn local(base: &str) -> Self {
+        let base = base.trim_end_matches('/').to_string();
+        Self {
+            auth: base.clone(),
+            entitlements: base.clone(),
+            geo: base.clone(),
+            pd: base.clone(),
+            glz: base.clone(),
+            shared: base.clone(),
+            valorant_api: base,
+        }
+    }
+
+    pub fn pd(&self, region: Region) -> String {
+        Self::expand(&self.pd, region)
+    }
+
+    pub fn glz(&self, region: Region) -> String {
+        Self::expand(&self.glz, region)
+    }
+
+    pub fn shared(&self, region: Region) -> String {
+        Self::expand(&self.shared, region)
+    }
+
+    fn expand(template: &str, region: Region) -> String {
+        template
+            .replace("{region}", region.glz_region())
+            .replace("{shard}", region.shard().as_str())
+    }
+}
+
+/// Shared state for every val-api call.
+///
+/// Cloning is cheap and keeps the connection pool, so a clone per account
+/// with its own tokens is the expected usage.
+#[derive(Debug, Clone)]
+pub struct ValClient {
+    http: reqwest::Client,
+    /// Built on the first auth request, commands that never log in keep
+    /// working when the system certificates cannot be read.
+    auth_http: Arc<OnceLock<Result<reqwest::Client, String>>>,
+    urls: BaseUrls,
+    retry: RetryPolicy,
+    platform: String,
+    client_version: Option<String>,
+    access_token: Option<String>,
+    entitlements_token: Option<String>,
+}
+
+impl Default for ValClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ValClient {
+    pub fn new() -> Self {
+        Self::with_base_urls(BaseUrls::default())
+    }
+
+    pub fn with_base_urls(urls: BaseUrls) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            auth_http: Arc::default(),
+            urls,
+            retry: RetryPolicy::default(),
+            platform: DEFAULT_PLATFORM.to_string(),
+            client_version: None,
+            access_token: None,
+            entitlements_token: None,
+        }
+    }
+
+    pub fn urls(&self) -> &BaseUrls {
+        &self.urls
+    }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    pub fn set_retry_policy(&mut self, retry: RetryPolicy) {
+        self.retry = retry;
+    }
+
+    pub fn platform(&self) -> &str {
+        &self.platform
+    }
+
+    pub fn client_version(&self) -> Option<&str> {
+        self.client_version.as_deref()
+    }
+
+    pub fn access_token(&self) -> Option<&str> {
+        self.access_token.as_deref()
+    }
+
+    pub fn entitlements_token(&self) -> Option<&str> {
+        self.entitlements_token.as_deref()
+    }
+
+    pub fn set_platform(&mut self, platform: impl Into<String>) {
+        self.platform = platform.into();
+    }
+
+    pub fn set_client_version(&mut self, client_version: impl Into<String>) {
+        self.client_version = Some(client_version.into());
+    }
+
+    pub fn set_access_token(&mut self, access_token: impl Into<String>) {
+        self.access_token = Some(access_token.into());
+    }
+
+    pub fn set_entitlements_token(&mut self, entitlements_token: impl Into<String>) {
+        self.entitlements_token = Some(entitlements_token.into());
+    }
+
+    /// Clone of this client authenticated as a specific account.
+    pub fn with_tokens(&self, access_token: &str, entitlements_token: &str) -> Self {
+        let mut client = self.clone();
+        client.set_access_token(access_token);
+        client.set_entitlements_token(entitlements_token);
+        client
+    }
+
+    pub(crate) fn http(&self) -> &reqwest::Client {
+        &self.http
+    }
+
+    /// Client restricted to TLS 1.3 and without redirects, required by the
+    /// riot auth servers.
+    ///
+    /// Auth requests carry credentials, codes or single use cookies, they are
+    /// sent with `send_once` unless known to be safe with `send_idempotent`.
+    pub(crate) fn auth_http(&self) -> Result<&reqwest::Client> {
+        self.auth_http
+            .get_or_init(build_auth_client)
+            .as_ref()
+            .map_err(|reason| ValApiError::AuthClient(reason.clone()))
+    }
+
+    /// Sends the request, mapping transport and status failures and
+    /// retrying them according to the retry policy.
+    pub(crate) async fn send(&self, builder: RequestBuilder) -> Result<Response> {
+        let idempotent = builder
+            .try_clone()
+            .and_then(|builder| builder.build().ok())
+            .is_some_and(|request| request.method().is_idempotent());
+
+        self.send_with_retry(builder, idempotent).await
+    }
+
+    /// Like `send`, for requests using a non idempotent method that are
+    /// still safe to repeat, e.g. read only POST endpoints.
+    pub(crate) async fn send_idempotent(&self, builder: RequestBuilder) -> Result<Response> {
+        self.send_with_retry(builder, true).await
+    }
+
+    /// Sends the request a single time, only mapping transport and status failures.
+    pub(crate) async fn send_once(&self, builder: RequestBuilder) -> Result<Response> {
+        error::check_status(builder.send().await?).await
+    }
+
+    async fn send_with_retry(&self, builder: RequestBuilder, idempotent: bool) -> Result<Response> {
+        let mut attempt = 1;
+        loop {
+            // streaming bodies cannot be cloned, those get a single attempt
+            let Some(request) = builder.try_clone() else {
+                return error::check_status(builder.send().await?).await;
+            };
+
+            let result = match request.send().await {
+                Ok(res) => error::check_status(res).await,
+                Err(err) => Err(err.into()),
+            };
+
+            let err = match result {
+                Ok(res) => return Ok(res),
+                Err(err) => err,
+            };
+
+            match self.retry.retry_delay(&err, attempt, idempotent) {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Err(err),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Adds the bearer access token to the request.
+    pub(crate) fn authorized(&self, builder: RequestBuilder) -> RequestBuilder {
+        match &self.access_token {
+            Some(token) => builder.header(header::AUTHORIZATION, format!("Bearer {token}")),
+            None => builder,
+        }
+    }
+
+    /// Adds the headers expected by the pd/glz game services.
+    pub(crate) fn game(&self, builder: RequestBuilder) -> RequestBuilder {
+        let builder = self
+            .authorized(builder)
+            .header("X-Riot-ClientPlatform", &self.platform);
+        let builder = match &self.client_version {
+            Some(version) => builder.header("X-Riot-ClientVersion", version),
+            None => builder,
+        };
+        match &self.entitlements_token {
+            Some(token) => builder.header("X-Riot-Entitlements-JWT", token),
+            None => builder,
+        }
+    }
+}
+
+fn build_auth_client() -> Result<reqwest::Client, String> {
+    let root_store = {
+        let certs = load_native_certs()
+            .map_err(|err| format!("failed to load system certificates: {err}"))?;
+        let mut root_store = rustls::RootCertStore::empty();
+        root_store.add_parsable_certificates(certs);
+        root_store
+    };
+
+    let crypto_provider = CryptoProvider {
+        cipher_suites: vec![
+            TLS13_AES_128_GCM_SHA256,
+            TLS13_AES_256_GCM_SHA384,
+            TLS13_CHACHA20_POLY1305_SHA256,
+        ],
+        kx_groups: aws_lc_rs::ALL_KX_GROUPS.to_vec(),
+        ..aws_lc_rs::default_provider()
+    };
+
+    let tls = ClientConfig::builder_with_provider(Arc::new(crypto_provider))
+        .with_protocol_versions(&[&TLS13])
+        .map_err(|err| format!("invalid TLS configuration: {err}"))?
+        .with_root_certificates(root_store)
+        .with_no_client_auth();
+
+    reqwest::ClientBuilder::new()
+        .redirect(Policy::none())
+        .use_preconfigured_tls(tls)
+        .build()
+        .map_err(|err| format!("failed to build client: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::atomic::Ordering, time::Duration};
+
+    use super::*;
+    use crate::{error::ValApiError, test_server::serve};
+
+    fn client(base: &str) -> ValClient {
+        let mut client = ValClient::with_base_urls(BaseUrls::local(base));
+        client.set_retry_policy(RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            jitter: false,
+            ..RetryPolicy::default()
+        });
+        client
+    }
+
+    #[tokio::test]
+    async fn rate_limited_request_is_retried() {
+        let (base, requests) = serve(&[
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+        ])
+        .await;
+        let client = client(&base);
+
+        let res = client.send(client.http().get(&base)).await.unwrap();
+
+        assert_eq!(res.text().await.unwrap(), "ok");
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn send_once_is_not_retried() {
+        let (base, requests) = serve(&[
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ])
+        .await;
+        let client = client(&base);
+
+        let err = client
+            .send_once(client.auth_http().unwrap().put(&base))
+            .await;
+
+        assert!(matches!(err, Err(ValApiError::Status { .. })));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
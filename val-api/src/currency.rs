@@ -0,0 +1,74 @@
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Currency as identified by its uuid in prices and wallet balances.
+///
+/// Ids that are not known yet are kept as `Unknown` so they survive a
+/// deserialize/serialize round trip.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Currency {
+    ValorantPoints,
+    RadianitePoints,
+    KingdomCredits,
+    FreeAgents,
+    Unknown(String),
+}
+
+impl Currency {
+    pub const KNOWN: [Currency; 4] = [
+        Currency::ValorantPoints,
+        Currency::RadianitePoints,
+        Currency::KingdomCredits,
+        Currency::FreeAgents,
+    ];
+
+    pub fn from_id(id: &str) -> Self {
+        match id {
+            "85ad13f7-3d1b-5128-9eb2-7cd8ee0b5741" => Currency::ValorantPoints,
+            "e59aa87c-4cbf-517a-5983-6e81511be9b7" => Currency::RadianitePoints,
+            "85ca954a-41f2-ce94-9b45-8ca3dd39a00d" => Currency::KingdomCredits,
+            "f08d4ae3-939c-4576-ab26-09ce1f23bb37" => Currency::FreeAgents,
+            id => Currency::Unknown(id.to_string()),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            Currency::ValorantPoints => "85ad13f7-3d1b-5128-9eb2-7cd8ee0b5741",
+            Currency::RadianitePoints => "e59aa87c-4cbf-517a-5983-6e81511be9b7",
+            Currency::KingdomCredits => "85ca954a-41f2-ce94-9b45-8ca3dd39a00d",
+            Currency::FreeAgents => "f08d4ae3-939c-4576-ab26-09ce1f23bb37",
+            Currency::Unknown(id) => id,
+        }
+    }
+
+    pub fn short_name(&self) -> &str {
+        match self {
+            Currency::ValorantPoints => "VP",
+            Currency::RadianitePoints => "RP",
+            Currency::KingdomCredits => "KC",
+            Currency::FreeAgents => "FA",
+            Currency::Unknown(id) => id,
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.short_name())
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.id())
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        Ok(Currency::from_id(&id))
+    }
+}
@@ -1,99 +1,470 @@
-use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use cookie_store::CookieExpiration;
+use reqwest::{header, RequestBuilder, Response};
+use reqwest_cookie_store::{CookieStore, RawCookie};
+use serde::Deserialize;
 
-use reqwest::{
-    header::{self},
-    redirect::Policy,
-};
-use rustls::{
-    crypto::{
-        aws_lc_rs::{
-            self,
-            cipher_suite::{
-                TLS13_AES_128_GCM_SHA256, TLS13_AES_256_GCM_SHA384, TLS13_CHACHA20_POLY1305_SHA256,
-            },
-        },
-        CryptoProvider,
+use crate::{
+    client::ValClient,
+    error::{self, Result},
+    models::{
+        AuthClientRequest, LoginRequest, LoginResponse, MultifactorDetails, MultifactorRequest,
+        Tokens,
     },
-    version::TLS13,
-    ClientConfig,
 };
-use rustls_native_certs::load_native_certs;
 
-use crate::models::Tokens;
+macro_rules! authorize_query {
+    () => {
+        concat!(
+            "redirect_uri=https%3A%2F%2Fplayvalorant.com%2Fopt_in&",
+            "client_id=play-valorant-web-prod&",
+            "response_type=token%20id_token&",
+            "scope=account%20openid&",
+            "nonce=1"
+        )
+    };
+}
 
-pub async fn silent_login(cookies: &String) -> Option<(Tokens, String)> {
-    let cookie_store = reqwest_cookie_store::CookieStoreMutex::default();
-    let cookie_store = std::sync::Arc::new(cookie_store);
+pub const AUTHORIZE_QUERY: &str = authorize_query!();
 
-    const ENDPOINT: &str = concat!(
-        "https://auth.riotgames.com/authorize?",
-        "redirect_uri=https%3A%2F%2Fplayvalorant.com%2Fopt_in&",
-        "client_id=play-valorant-web-prod&",
-        "response_type=token%20id_token&",
-        "scope=account%20openid&",
-        "nonce=1"
-    );
+/// Login page of the web client, redirects to `playvalorant.com/opt_in` with the tokens.
+pub const RIOT_AUTH_PAGE: &str =
+    concat!("https://auth.riotgames.com/authorize?", authorize_query!());
 
-    // Prepare TLS client
-    let root_store = {
-        let certs = load_native_certs().expect("system certificates should load");
-        let mut root_store = rustls::RootCertStore::empty();
-        root_store.add_parsable_certificates(certs);
-        root_store
-    };
+/// Like `RIOT_AUTH_PAGE` but asks for the credentials even when the browser
+/// still holds a session, used to switch to another account.
+pub const RIOT_LOGIN_PROMPT_PAGE: &str = concat!(
+    "https://auth.riotgames.com/authorize?",
+    authorize_query!(),
+    "&prompt=login"
+);
 
-    let crypto_provider = CryptoProvider {
-        cipher_suites: vec![
-            TLS13_AES_128_GCM_SHA256,
-            TLS13_AES_256_GCM_SHA384,
-            TLS13_CHACHA20_POLY1305_SHA256,
-        ],
-        kx_groups: aws_lc_rs::ALL_KX_GROUPS.to_vec(),
-        ..aws_lc_rs::default_provider()
-    };
+/// Where Riot sends the browser when the cookies no longer hold a session.
+const LOGIN_HOSTS: [&str; 2] = ["authenticate.riotgames.com", "auth.riotgames.com"];
 
-    let tls = ClientConfig::builder_with_provider(Arc::new(crypto_provider))
-        .with_protocol_versions(&[&TLS13])
-        .expect("configuration should be valid")
-        .with_root_certificates(root_store)
-        .with_no_client_auth();
+/// Result of reusing stored cookies to get fresh tokens.
+///
+/// Network and server failures are returned as `ValApiError` instead, those
+/// say nothing about the validity of the cookies.
+#[derive(Debug)]
+pub enum SilentLoginOutcome {
+    /// The cookies are still valid, `cookies` holds them merged with the rotated ones.
+    LoggedIn {
+        tokens: Tokens,
+        cookies: CookieStore,
+    },
+    /// The session behind the cookies is gone and the user has to log in again.
+    ReauthRequired,
+    /// Riot redirected back with an error code, e.g. `access_denied`.
+    AuthError {
+        code: String,
+        description: Option<String>,
+    },
+    /// Riot answered without a redirect or redirected somewhere unknown.
+    UnexpectedRedirect { location: Option<String> },
+}
 
-    let client = reqwest::ClientBuilder::new()
-        .cookie_store(true)
-        .cookie_provider(cookie_store.clone())
-        .redirect(Policy::none())
-        .use_preconfigured_tls(tls)
-        .build()
-        .unwrap();
+#[derive(Deserialize)]
+struct AuthErrorParams {
+    error: String,
+    error_description: Option<String>,
+}
+
+pub async fn silent_login(client: &ValClient, cookies: &CookieStore) -> Result<SilentLoginOutcome> {
+    let endpoint = error::parse_url(format!(
+        "{}/authorize?{}",
+        client.urls().auth,
+        AUTHORIZE_QUERY
+    ))?;
 
+    // only reads the session cookie, repeating it cannot log in twice
     let res = client
-        .get(ENDPOINT)
-        .header(header::COOKIE, cookies)
-        .send()
-        .await
-        .unwrap();
+        .send_idempotent(
+            client
+                .auth_http()?
+                .get(endpoint.clone())
+                .header(header::COOKIE, cookie_header(cookies, &endpoint)),
+        )
+        .await?;
 
     let location = res
         .headers()
         .get(header::LOCATION)
-        .and_then(|loc| loc.to_str().ok());
-
-    let tokens: Tokens = location
-        .and_then(|str| url::Url::parse(str).ok())
-        .and_then(|url| {
-            url.fragment()
-                .and_then(|frag| serde_urlencoded::from_str(frag).ok())
-        })?;
-
-    let new_cookies = {
-        let store = cookie_store.lock().unwrap();
-        let cookies =
-            store.get_request_values(&"https://auth.riotgames.com/authorize".parse().unwrap());
-        cookies
-            .map(|it| format!("{}={}", it.0, it.1))
-            .collect::<Vec<_>>()
-            .join("; ")
+        .and_then(|loc| loc.to_str().ok())
+        .map(str::to_string);
+
+    // cookies that were not re-issued stay valid, only replace the rotated ones
+    let mut cookies = cookies.clone();
+    store_set_cookies(&mut cookies, &res);
+
+    Ok(classify_redirect(res.url(), location, cookies))
+}
+
+/// Outcome of the redirect answered to the `silent_login` request to `url`,
+/// `cookies` are only kept when it carries the tokens.
+fn classify_redirect(
+    url: &url::Url,
+    location: Option<String>,
+    cookies: CookieStore,
+) -> SilentLoginOutcome {
+    let Some(redirect) = location.as_deref().and_then(|loc| url.join(loc).ok()) else {
+        return SilentLoginOutcome::UnexpectedRedirect { location };
+    };
+
+    // errors can come back in the fragment or the query depending on the flow
+    let params = redirect.fragment().or(redirect.query()).unwrap_or_default();
+    if let Ok(error) = serde_urlencoded::from_str::<AuthErrorParams>(params) {
+        return match error.error.as_str() {
+            "login_required" | "consent_required" | "interaction_required" => {
+                SilentLoginOutcome::ReauthRequired
+            }
+            _ => SilentLoginOutcome::AuthError {
+                code: error.error,
+                description: error.error_description,
+            },
+        };
+    }
+
+    if let Some(tokens) = tokens_from_url(&redirect) {
+        return SilentLoginOutcome::LoggedIn { tokens, cookies };
+    }
+
+    let is_login_page = redirect
+        .host_str()
+        .is_some_and(|host| LOGIN_HOSTS.contains(&host) || Some(host) == url.host_str());
+    if is_login_page {
+        SilentLoginOutcome::ReauthRequired
+    } else {
+        SilentLoginOutcome::UnexpectedRedirect { location }
+    }
+}
+
+/// Result of a step of the username and password login.
+#[derive(Debug)]
+pub enum PasswordLoginOutcome {
+    /// `cookies` can be stored and used with `silent_login` later on.
+    LoggedIn {
+        tokens: Tokens,
+        cookies: CookieStore,
+    },
+    /// A code was sent to the user, continue with `submit_multifactor_code`.
+    MultifactorRequired(MultifactorChallenge),
+    /// Riot rejected the attempt, e.g. `auth_failure` for wrong credentials.
+    Failed { error: String },
+}
+
+/// Pending login waiting for the multifactor code.
+#[derive(Debug)]
+pub struct MultifactorChallenge {
+    pub details: MultifactorDetails,
+    cookies: CookieStore,
+}
+
+/// Logs in with a username and password without a browser.
+pub async fn password_login(
+    client: &ValClient,
+    username: &str,
+    password: &str,
+) -> Result<PasswordLoginOutcome> {
+    let endpoint = authorization_endpoint(client)?;
+    let mut cookies = CookieStore::default();
+
+    // sets the asid cookie the credentials are checked against
+    let request = AuthClientRequest {
+        client_id: "play-valorant-web-prod".to_string(),
+        nonce: "1".to_string(),
+        redirect_uri: "https://playvalorant.com/opt_in".to_string(),
+        response_type: "token id_token".to_string(),
+        scope: "account openid".to_string(),
+    };
+    authorization_request(
+        client,
+        &mut cookies,
+        client.auth_http()?.post(endpoint.clone()).json(&request),
+    )
+    .await?;
+
+    let request = LoginRequest {
+        action_type: "auth".to_string(),
+        username: username.to_string(),
+        password: password.to_string(),
+        remember: true,
+    };
+    let res = authorization_request(
+        client,
+        &mut cookies,
+        client.auth_http()?.put(endpoint).json(&request),
+    )
+    .await?;
+
+    Ok(login_outcome(res, cookies))
+}
+
+/// Finishes a login that required a multifactor code.
+pub async fn submit_multifactor_code(
+    client: &ValClient,
+    challenge: MultifactorChallenge,
+    code: &str,
+) -> Result<PasswordLoginOutcome> {
+    let endpoint = authorization_endpoint(client)?;
+    let mut cookies = challenge.cookies;
+
+    let request = MultifactorRequest {
+        action_type: "multifactor".to_string(),
+        code: code.trim().to_string(),
+        remember_device: true,
+    };
+    let res = authorization_request(
+        client,
+        &mut cookies,
+        client.auth_http()?.put(endpoint).json(&request),
+    )
+    .await?;
+
+    Ok(login_outcome(res, cookies))
+}
+
+fn authorization_endpoint(client: &ValClient) -> Result<url::Url> {
+    error::parse_url(format!("{}/api/v1/authorization", client.urls().auth))
+}
+
+/// Sends a request with the cookies of the login so far and keeps the ones set by the answer.
+async fn authorization_request(
+    client: &ValClient,
+    cookies: &mut CookieStore,
+    builder: RequestBuilder,
+) -> Result<LoginResponse> {
+    let builder = match builder.try_clone().and_then(|it| it.build().ok()) {
+        Some(request) => builder.header(header::COOKIE, cookie_header(cookies, request.url())),
+        None => builder,
     };
 
-    Some((tokens, new_cookies))
+    // a replayed request would send the password or code again
+    let res = client.send_once(builder).await?;
+    store_set_cookies(cookies, &res);
+
+    error::json(res).await
+}
+
+fn login_outcome(res: LoginResponse, cookies: CookieStore) -> PasswordLoginOutcome {
+    if let Some(error) = res.error {
+        return PasswordLoginOutcome::Failed { error };
+    }
+
+    match res.response_type.as_str() {
+        "multifactor" => PasswordLoginOutcome::MultifactorRequired(MultifactorChallenge {
+            details: res.multifactor.unwrap_or_default(),
+            cookies,
+        }),
+        "response" => {
+            let tokens = res
+                .response
+                .and_then(|data| tokens_from_redirect(&data.parameters.uri));
+            match tokens {
+                Some(tokens) => PasswordLoginOutcome::LoggedIn { tokens, cookies },
+                None => PasswordLoginOutcome::Failed {
+                    error: "missing tokens in response".to_string(),
+                },
+            }
+        }
+        other => PasswordLoginOutcome::Failed {
+            error: format!("unexpected response type {other}"),
+        },
+    }
+}
+
+/// Reads the tokens from the `playvalorant.com/opt_in#access_token=...`
+/// url Riot redirects to once logged in.
+pub fn tokens_from_redirect(uri: &str) -> Option<Tokens> {
+    url::Url::parse(uri.trim())
+        .ok()
+        .as_ref()
+        .and_then(tokens_from_url)
+}
+
+fn tokens_from_url(url: &url::Url) -> Option<Tokens> {
+    url.fragment()
+        .and_then(|frag| serde_urlencoded::from_str(frag).ok())
+}
+
+/// Expiry of the `ssid` cookie, once it passes `silent_login` stops working
+/// and the user has to log in again.
+pub fn session_cookie_expires_at(cookies: &CookieStore) -> Option<DateTime<Utc>> {
+    cookies
+        .iter_unexpired()
+        .filter(|cookie| cookie.name() == "ssid")
+        .find_map(|cookie| match cookie.expires {
+            CookieExpiration::AtUtc(expires) => {
+                DateTime::from_timestamp(expires.unix_timestamp(), 0)
+            }
+            CookieExpiration::SessionEnd => None,
+        })
+}
+
+fn store_set_cookies(store: &mut CookieStore, res: &Response) {
+    let set_cookies = res
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| RawCookie::parse(value.to_string()).ok());
+    store.store_response_cookies(set_cookies, res.url());
+}
+
+fn cookie_header(store: &CookieStore, url: &url::Url) -> String {
+    store
+        .get_request_values(url)
+        .map(|it| format!("{}={}", it.0, it.1))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+    use crate::{client::BaseUrls, error::ValApiError, retry::RetryPolicy, test_server::serve};
+
+    const AUTH_TYPE: &str =
+        "HTTP/1.1 200 OK\r\nContent-Length: 15\r\nConnection: close\r\n\r\n{\"type\":\"auth\"}";
+    const UNAVAILABLE: &str =
+        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const TOKEN_REDIRECT: &str = concat!(
+        "HTTP/1.1 303 See Other\r\n",
+        "Location: https://playvalorant.com/opt_in#access_token=a&id_token=b&expires_in=3600\r\n",
+        "Set-Cookie: ssid=rotated; Path=/\r\n",
+        "Content-Length: 0\r\nConnection: close\r\n\r\n"
+    );
+
+    fn describe(outcome: SilentLoginOutcome) -> String {
+        match outcome {
+            SilentLoginOutcome::LoggedIn { tokens, .. } => {
+                format!("tokens {} {}", tokens.access_token, tokens.id_token)
+            }
+            SilentLoginOutcome::ReauthRequired => "reauth".to_string(),
+            SilentLoginOutcome::AuthError { code, description } => {
+                format!("error {code} {description:?}")
+            }
+            SilentLoginOutcome::UnexpectedRedirect { location } => {
+                format!("unexpected {location:?}")
+            }
+        }
+    }
+
+    #[test]
+    fn redirects_are_classified() {
+        let cases = [
+            (
+                Some("https://playvalorant.com/opt_in#access_token=a&id_token=b&expires_in=3600"),
+                "tokens a b",
+            ),
+            (
+                Some("https://authenticate.riotgames.com/?client_id=play-valorant-web-prod"),
+                "reauth",
+            ),
+            (Some("/login"), "reauth"),
+            (
+                Some("https://playvalorant.com/opt_in#error=login_required"),
+                "reauth",
+            ),
+            (
+                Some("https://playvalorant.com/opt_in?error=consent_required"),
+                "reauth",
+            ),
+            (
+                Some(
+                    "https://playvalorant.com/opt_in#error=access_denied&error_description=banned",
+                ),
+                r#"error access_denied Some("banned")"#,
+            ),
+            (
+                Some("https://playvalorant.com/opt_in?error=server_error"),
+                "error server_error None",
+            ),
+            (
+                Some("https://example.com/"),
+                r#"unexpected Some("https://example.com/")"#,
+            ),
+            (
+                Some("https://playvalorant.com/opt_in"),
+                r#"unexpected Some("https://playvalorant.com/opt_in")"#,
+            ),
+            (None, "unexpected None"),
+        ];
+        let url = url::Url::parse(RIOT_AUTH_PAGE).unwrap();
+
+        for (location, expected) in cases {
+            let outcome =
+                classify_redirect(&url, location.map(str::to_string), CookieStore::default());
+            assert_eq!(describe(outcome), expected, "{location:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn silent_login_keeps_rotated_cookies() {
+        let (base, _) = serve(&[TOKEN_REDIRECT]).await;
+        let client = ValClient::with_base_urls(BaseUrls::local(&base));
+
+        let outcome = silent_login(&client, &CookieStore::default()).await;
+
+        let Ok(SilentLoginOutcome::LoggedIn { tokens, cookies }) = outcome else {
+            panic!("expected a login, got {outcome:?}");
+        };
+        assert_eq!(tokens.access_token, "a");
+        assert!(cookies
+            .iter_unexpired()
+            .any(|cookie| cookie.name() == "ssid" && cookie.value() == "rotated"));
+    }
+
+    #[tokio::test]
+    async fn silent_login_reports_server_errors() {
+        let (base, _) = serve(&[UNAVAILABLE]).await;
+        let mut client = ValClient::with_base_urls(BaseUrls::local(&base));
+        client.set_retry_policy(RetryPolicy::none());
+
+        let outcome = silent_login(&client, &CookieStore::default()).await;
+
+        assert!(matches!(outcome, Err(ValApiError::Status { .. })));
+    }
+
+    #[tokio::test]
+    async fn silent_login_reports_transport_failures() {
+        // nothing listens on the port once the listener is dropped
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let mut client = ValClient::with_base_urls(BaseUrls::local(&base));
+        client.set_retry_policy(RetryPolicy::none());
+
+        let outcome = silent_login(&client, &CookieStore::default()).await;
+
+        assert!(matches!(outcome, Err(ValApiError::Transport(_))));
+    }
+
+    #[tokio::test]
+    async fn credentials_are_not_replayed() {
+        let (base, requests) = serve(&[AUTH_TYPE, UNAVAILABLE, AUTH_TYPE]).await;
+        let client = ValClient::with_base_urls(BaseUrls::local(&base));
+
+        let outcome = password_login(&client, "user", "password").await;
+
+        assert!(matches!(outcome, Err(ValApiError::Status { .. })));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn multifactor_code_is_not_replayed() {
+        let (base, requests) = serve(&[UNAVAILABLE, AUTH_TYPE]).await;
+        let client = ValClient::with_base_urls(BaseUrls::local(&base));
+        let challenge = MultifactorChallenge {
+            details: MultifactorDetails::default(),
+            cookies: CookieStore::default(),
+        };
+
+        let outcome = submit_multifactor_code(&client, challenge, "123456").await;
+
+        assert!(matches!(outcome, Err(ValApiError::Status { .. })));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
 }
@@ -0,0 +1,44 @@
+use crate::{
+    client::ValClient,
+    error::{self, Result, ValApiError},
+    models::ContentResponse,
+    region::Region,
+    season::Season,
+    thirdparty::valdata,
+};
+
+pub async fn content_fetch(client: &ValClient, region: Region) -> Result<ContentResponse> {
+    let url = format!(
+        "{}/content-service/v3/content",
+        client.urls().shared(region)
+    );
+
+    let res = client.send(client.game(client.http().get(url))).await?;
+
+    error::json(res).await
+}
+
+/// Seasons with the content service error when they came from the fallback.
+#[derive(Debug)]
+pub struct Seasons {
+    pub seasons: Vec<Season>,
+    pub content_error: Option<ValApiError>,
+}
+
+/// Seasons from the content service, falling back to valorant-api when it fails.
+pub async fn content_fetch_seasons(client: &ValClient, region: Region) -> Result<Seasons> {
+    match content_fetch(client, region).await {
+        Ok(content) => Ok(Seasons {
+            seasons: content.seasons,
+            content_error: None,
+        }),
+        // the fallback error is less useful than why the content service failed
+        Err(err) => match valdata::get_seasons(client).await {
+            Ok(seasons) => Ok(Seasons {
+                seasons,
+                content_error: Some(err),
+            }),
+            Err(_) => Err(err),
+        },
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{
+    client::ValClient,
+    error::{self, Result},
+    models::{MatchDetails, MatchHistory},
+    region::Region,
+};
+
+/// Matches between `start_index` and `end_index` (exclusive), newest first.
+pub async fn match_fetch_history(
+    client: &ValClient,
+    region: Region,
+    puuid: &str,
+    start_index: u32,
+    end_index: u32,
+    queue: Option<&str>,
+) -> Result<MatchHistory> {
+    let mut url = format!(
+        "{}/match-history/v1/history/{puuid}?startIndex={start_index}&endIndex={end_index}",
+        client.urls().pd(region)
+    );
+    if let Some(queue) = queue {
+        url.push_str("&queue=");
+        url.extend(url::form_urlencoded::byte_serialize(queue.as_bytes()));
+    }
+
+    let res = client.send(client.game(client.http().get(url))).await?;
+
+    error::json(res).await
+}
+
+pub async fn match_fetch_details(
+    client: &ValClient,
+    region: Region,
+    match_id: &str,
+) -> Result<MatchDetails> {
+    let url = format!(
+        "{}/match-details/v1/matches/{match_id}",
+        client.urls().pd(region)
+    );
+
+    let res = client.send(client.game(client.http().get(url))).await?;
+
+    error::json(res).await
+}
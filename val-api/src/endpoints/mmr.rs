@@ -0,0 +1,36 @@
+use crate::{
+    client::ValClient,
+    error::{self, Result},
+    models::{CompetitiveUpdates, PlayerMmr},
+    region::Region,
+};
+
+pub async fn mmr_fetch_player(
+    client: &ValClient,
+    region: Region,
+    puuid: &str,
+) -> Result<PlayerMmr> {
+    let url = format!("{}/mmr/v1/players/{puuid}", client.urls().pd(region));
+
+    let res = client.send(client.game(client.http().get(url))).await?;
+
+    error::json(res).await
+}
+
+/// Rank changes between `start_index` and `end_index` (exclusive), newest first.
+pub async fn mmr_fetch_competitive_updates(
+    client: &ValClient,
+    region: Region,
+    puuid: &str,
+    start_index: u32,
+    end_index: u32,
+) -> Result<CompetitiveUpdates> {
+    let url = format!(
+        "{}/mmr/v1/players/{puuid}/competitiveupdates?startIndex={start_index}&endIndex={end_index}&queue=competitive",
+        client.urls().pd(region)
+    );
+
+    let res = client.send(client.game(client.http().get(url))).await?;
+
+    error::json(res).await
+}
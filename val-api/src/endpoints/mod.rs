@@ -1,3 +1,8 @@
 pub mod auth;
+pub mod content;
+pub mod matches;
+pub mod mmr;
+pub mod name_service;
+pub mod personalization;
 pub mod store;
 pub mod user;
@@ -0,0 +1,20 @@
+use crate::{
+    client::ValClient,
+    error::{self, Result},
+    models::PlayerName,
+    region::Region,
+};
+
+pub async fn name_fetch_players(
+    client: &ValClient,
+    region: Region,
+    puuids: &[String],
+) -> Result<Vec<PlayerName>> {
+    let url = format!("{}/name-service/v2/players", client.urls().pd(region));
+
+    let res = client
+        .send(client.game(client.http().put(url)).json(puuids))
+        .await?;
+
+    error::json(res).await
+}
@@ -0,0 +1,21 @@
+use crate::{
+    client::ValClient,
+    error::{self, Result},
+    models::PlayerLoadout,
+    region::Region,
+};
+
+pub async fn personalization_fetch_loadout(
+    client: &ValClient,
+    region: Region,
+    puuid: &str,
+) -> Result<PlayerLoadout> {
+    let url = format!(
+        "{}/personalization/v2/players/{puuid}/playerloadout",
+        client.urls().pd(region)
+    );
+
+    let res = client.send(client.game(client.http().get(url))).await?;
+
+    error::json(res).await
+}
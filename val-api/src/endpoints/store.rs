@@ -1,52 +1,54 @@
-use crate::models::StoreFrontResponse;
-
-const PLATFORM: &str = "ew0KCSJwbGF0Zm9ybVR5cGUiOiAiUEMiLA0KCSJwbGF0Zm9ybU9TIjogIldpbmRvd3MiLA0KCSJwbGF0Zm9ybU9TVmVyc2lvbiI6ICIxMC4wLjE5MDQyLjEuMjU2LjY0Yml0IiwNCgkicGxhdGZvcm1DaGlwc2V0IjogIlVua25vd24iDQp9";
-
-pub async fn store_fetch_offers(
-    client_ver: String,
-    ent: String,
-    access_token: String,
-    shard: String,
-) {
-    let url = format!("https://pd.{shard}.a.pvp.net/store/v1/offers/");
-
-    let client = reqwest::Client::new();
-
-    let res = client
-        .get(url)
-        .header("X-Riot-ClientPlatform", PLATFORM)
-        .header("X-Riot-ClientVersion", client_ver)
-        .header("X-Riot-Entitlements-JWT", ent)
-        .header("Authorization", format!("Bearer {}", access_token))
-        .send()
-        .await
-        .unwrap();
+use crate::{
+    client::ValClient,
+    error::{self, Result},
+    item_type::ItemType,
+    models::{EntitlementsResponse, OffersCatalog, OffersResponse, StoreFrontResponse, Wallet},
+    region::Region,
+};
+
+pub async fn store_fetch_offers(client: &ValClient, region: Region) -> Result<OffersCatalog> {
+    let url = format!("{}/store/v1/offers/", client.urls().pd(region));
 
-    println!("{}", res.text().await.unwrap());
+    let res = client.send(client.game(client.http().get(url))).await?;
+
+    error::json::<OffersResponse>(res)
+        .await
+        .map(OffersCatalog::from)
 }
 
 pub async fn store_fetch_storefront(
-    client_ver: &str,
-    ent: &str,
-    access_token: &str,
-    shard: &str,
+    client: &ValClient,
+    region: Region,
     puuid: &str,
-) -> StoreFrontResponse {
-    let url = format!("https://pd.{shard}.a.pvp.net/store/v2/storefront/{puuid}");
-
-    let client = reqwest::Client::new();
-
-    let res = client
-        .get(url)
-        .header("X-Riot-ClientPlatform", PLATFORM)
-        .header("X-Riot-ClientVersion", client_ver)
-        .header("X-Riot-Entitlements-JWT", ent)
-        .header("Authorization", format!("Bearer {}", access_token))
-        .send()
-        .await
-        .unwrap();
+) -> Result<StoreFrontResponse> {
+    let url = format!("{}/store/v2/storefront/{puuid}", client.urls().pd(region));
+
+    let res = client.send(client.game(client.http().get(url))).await?;
+
+    error::json(res).await
+}
+
+pub async fn store_fetch_wallet(client: &ValClient, region: Region, puuid: &str) -> Result<Wallet> {
+    let url = format!("{}/store/v1/wallet/{puuid}", client.urls().pd(region));
+
+    let res = client.send(client.game(client.http().get(url))).await?;
+
+    error::json(res).await
+}
+
+pub async fn store_fetch_entitlements(
+    client: &ValClient,
+    region: Region,
+    puuid: &str,
+    item_type: &ItemType,
+) -> Result<EntitlementsResponse> {
+    let url = format!(
+        "{}/store/v1/entitlements/{puuid}/{}",
+        client.urls().pd(region),
+        item_type.id()
+    );
 
-    let json = res.text().await.unwrap();
+    let res = client.send(client.game(client.http().get(url))).await?;
 
-    serde_json::from_str(&json).unwrap()
+    error::json(res).await
 }
@@ -1,66 +1,54 @@
 use reqwest::header::{self, HeaderValue};
 
-use crate::models::{EntitlementsTokenResponse, RegionRequest, RegionResponse, UserInfoResponse};
+use crate::{
+    client::ValClient,
+    error::{self, Result},
+    models::{EntitlementsTokenResponse, RegionRequest, RegionResponse, UserInfoResponse},
+};
 
 const CONTENT_TYPE: HeaderValue = HeaderValue::from_static("application/json");
 
-pub async fn get_entitlements_token(access_token: &str) -> String {
-    let mut headers = header::HeaderMap::new();
-    headers.insert(header::CONTENT_TYPE, CONTENT_TYPE);
-    headers.insert(
-        header::AUTHORIZATION,
-        HeaderValue::from_str(format!("Bearer {}", access_token).as_str()).unwrap(),
-    );
+pub async fn get_entitlements_token(client: &ValClient) -> Result<String> {
+    let endpoint = format!("{}/api/token/v1", client.urls().entitlements);
 
-    let client = reqwest::Client::new();
-
-    const ENDPOINT: &str = "https://entitlements.auth.riotgames.com/api/token/v1";
-
-    let res = client.post(ENDPOINT).headers(headers).send().await.unwrap();
+    let res = client
+        .send_idempotent(
+            client
+                .authorized(client.http().post(endpoint))
+                .header(header::CONTENT_TYPE, CONTENT_TYPE),
+        )
+        .await?;
 
-    let res = res.json::<EntitlementsTokenResponse>().await.unwrap();
+    let res = error::json::<EntitlementsTokenResponse>(res).await?;
 
-    res.entitlements_token
+    Ok(res.entitlements_token)
 }
 
-pub async fn get_user_info(access_token: &str) -> (String, UserInfoResponse) {
-    let mut headers = header::HeaderMap::new();
-    headers.insert(header::CONTENT_TYPE, CONTENT_TYPE);
-    headers.insert(
-        header::AUTHORIZATION,
-        HeaderValue::from_str(format!("Bearer {}", access_token).as_str()).unwrap(),
-    );
-
-    let client = reqwest::Client::new();
-
-    const ENDPOINT: &str = "https://auth.riotgames.com/userinfo";
+pub async fn get_user_info(client: &ValClient) -> Result<(String, UserInfoResponse)> {
+    let endpoint = format!("{}/userinfo", client.urls().auth);
 
-    let res = client.post(ENDPOINT).headers(headers).send().await.unwrap();
-    let text = res.text().await.unwrap();
-
-    let user_info = serde_json::from_str::<UserInfoResponse>(text.as_str()).unwrap();
-
-    (text, user_info)
+    let res = client
+        .send_idempotent(
+            client
+                .authorized(client.http().post(endpoint))
+                .header(header::CONTENT_TYPE, CONTENT_TYPE),
+        )
+        .await?;
+
+    error::read_json(res).await
 }
 
-pub async fn get_region(access_token: &String, id_token: String) -> RegionResponse {
-    let mut headers = header::HeaderMap::new();
-    headers.insert(header::CONTENT_TYPE, CONTENT_TYPE);
-    headers.insert(
-        header::AUTHORIZATION,
-        HeaderValue::from_str(format!("Bearer {}", access_token).as_str()).unwrap(),
-    );
+pub async fn get_region(client: &ValClient, id_token: String) -> Result<RegionResponse> {
+    let endpoint = format!("{}/pas/v1/product/valorant", client.urls().geo);
 
-    let client = reqwest::Client::new();
-
-    const ENDPOINT: &str = "https://riot-geo.pas.si.riotgames.com/pas/v1/product/valorant";
     let res = client
-        .put(ENDPOINT)
-        .json(&RegionRequest { id_token })
-        .headers(headers)
-        .send()
-        .await
-        .unwrap();
-
-    res.json::<RegionResponse>().await.unwrap()
+        .send(
+            client
+                .authorized(client.http().put(endpoint))
+                .header(header::CONTENT_TYPE, CONTENT_TYPE)
+                .json(&RegionRequest { id_token }),
+        )
+        .await?;
+
+    error::json(res).await
 }
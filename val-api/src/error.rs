@@ -0,0 +1,180 @@
+use std::{fmt, time::Duration};
+
+use chrono::{DateTime, Utc};
+use reqwest::{header, Response, StatusCode};
+use serde::de::DeserializeOwned;
+
+pub type Result<T, E = ValApiError> = std::result::Result<T, E>;
+
+#[derive(Debug)]
+pub enum ValApiError {
+    /// The request never got a response (dns, tls, timeout, ...).
+    Transport(reqwest::Error),
+    /// The server answered with a non success status.
+    Status { status: StatusCode, body: String },
+    /// The response body did not match the expected model.
+    Decode {
+        source: serde_json::Error,
+        body: String,
+    },
+    /// The access or entitlements token was rejected.
+    AuthExpired,
+    /// The server answered 429, `retry_after` is taken from the header when present.
+    RateLimited { retry_after: Option<Duration> },
+    /// A token could not be decoded as a JWT.
+    InvalidToken(String),
+    /// An endpoint built from the configured base urls is not a valid url.
+    InvalidUrl {
+        url: String,
+        source: url::ParseError,
+    },
+    /// The TLS client required by the auth servers could not be set up,
+    /// e.g. because the system certificates cannot be read.
+    AuthClient(String),
+}
+
+impl fmt::Display for ValApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport(err) => write!(f, "request failed: {err}"),
+            Self::Status { status, body } => write!(f, "unexpected status {status}: {body}"),
+            Self::Decode { source, body } => {
+                write!(f, "failed to decode response ({source}): {body}")
+            }
+            Self::AuthExpired => write!(f, "authentication expired"),
+            Self::RateLimited {
+                retry_after: Some(retry_after),
+            } => write!(f, "rate limited, retry after {}s", retry_after.as_secs()),
+            Self::RateLimited { retry_after: None } => write!(f, "rate limited"),
+            Self::InvalidToken(reason) => write!(f, "invalid token: {reason}"),
+            Self::InvalidUrl { url, source } => write!(f, "invalid url {url}: {source}"),
+            Self::AuthClient(reason) => write!(f, "auth client unavailable: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ValApiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Transport(err) => Some(err),
+            Self::Decode { source, .. } => Some(source),
+            Self::InvalidUrl { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ValApiError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Transport(err)
+    }
+}
+
+pub(crate) fn parse_url(url: String) -> Result<url::Url> {
+    match url.parse() {
+        Ok(parsed) => Ok(parsed),
+        Err(source) => Err(ValApiError::InvalidUrl { url, source }),
+    }
+}
+
+/// Turns error statuses into the matching `ValApiError`.
+pub(crate) async fn check_status(res: Response) -> Result<Response> {
+    let status = res.status();
+    if status.is_success() || status.is_redirection() {
+        return Ok(res);
+    }
+
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = res
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_retry_after(value, Utc::now()));
+        return Err(ValApiError::RateLimited { retry_after });
+    }
+
+    let body = res.text().await?;
+
+    // pd answers 400 BAD_CLAIMS when the access or entitlements token expired
+    if status == StatusCode::UNAUTHORIZED
+        || (status == StatusCode::BAD_REQUEST && body.contains("BAD_CLAIMS"))
+    {
+        return Err(ValApiError::AuthExpired);
+    }
+
+    Err(ValApiError::Status { status, body })
+}
+
+/// `Retry-After` is either a number of seconds or an http date.
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<f64>() {
+        return (seconds.is_finite() && seconds >= 0.0).then(|| Duration::from_secs_f64(seconds));
+    }
+
+    // a date in the past means the request can be retried right away
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// Reads the body and decodes it, keeping the raw text around.
+pub(crate) async fn read_json<T: DeserializeOwned>(res: Response) -> Result<(String, T)> {
+    let body = res.text().await?;
+    match serde_json::from_str(&body) {
+        Ok(value) => Ok((body, value)),
+        Err(source) => Err(ValApiError::Decode { source, body }),
+    }
+}
+
+pub(crate) async fn json<T: DeserializeOwned>(res: Response) -> Result<T> {
+    read_json(res).await.map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 10, 21, 7, 28, 0).unwrap()
+    }
+
+    #[test]
+    fn retry_after_in_seconds() {
+        assert_eq!(
+            parse_retry_after("1.5", now()),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(
+            parse_retry_after(" 120 ", now()),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(parse_retry_after("-1", now()), None);
+    }
+
+    #[test]
+    fn retry_after_as_http_date() {
+        assert_eq!(
+            parse_retry_after("Mon, 21 Oct 2024 07:28:30 GMT", now()),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn retry_after_date_in_the_past() {
+        assert_eq!(
+            parse_retry_after("Mon, 21 Oct 2024 07:27:00 GMT", now()),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn retry_after_garbage() {
+        assert_eq!(parse_retry_after("soon", now()), None);
+    }
+}
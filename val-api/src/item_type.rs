@@ -0,0 +1,80 @@
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Kind of item as identified by its uuid in offers and entitlements.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ItemType {
+    SkinLevel,
+    SkinChroma,
+    Buddy,
+    Spray,
+    Card,
+    Title,
+    Agent,
+    Contract,
+    Unknown(String),
+}
+
+impl ItemType {
+    pub fn from_id(id: &str) -> Self {
+        match id {
+            "e7c63390-eda7-46e0-bb7a-a6abdacd2433" => ItemType::SkinLevel,
+            "3ad1b2b2-acdb-4524-852f-954a76ddae0a" => ItemType::SkinChroma,
+            "dd3bf334-87f3-40bd-b043-682a57a8dc3a" => ItemType::Buddy,
+            "d5f120f8-ff8c-4aac-92ea-f2b5acbe9475" => ItemType::Spray,
+            "3f296c07-64c3-494c-923b-fe692a4fa1bd" => ItemType::Card,
+            "de7caa6b-adf7-4588-bbd1-143831e786c6" => ItemType::Title,
+            "01bb38e1-da47-4e6a-9b3d-945fe4655707" => ItemType::Agent,
+            "f85cb6f7-33e5-4dc8-b609-ec7212301948" => ItemType::Contract,
+            id => ItemType::Unknown(id.to_string()),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            ItemType::SkinLevel => "e7c63390-eda7-46e0-bb7a-a6abdacd2433",
+            ItemType::SkinChroma => "3ad1b2b2-acdb-4524-852f-954a76ddae0a",
+            ItemType::Buddy => "dd3bf334-87f3-40bd-b043-682a57a8dc3a",
+            ItemType::Spray => "d5f120f8-ff8c-4aac-92ea-f2b5acbe9475",
+            ItemType::Card => "3f296c07-64c3-494c-923b-fe692a4fa1bd",
+            ItemType::Title => "de7caa6b-adf7-4588-bbd1-143831e786c6",
+            ItemType::Agent => "01bb38e1-da47-4e6a-9b3d-945fe4655707",
+            ItemType::Contract => "f85cb6f7-33e5-4dc8-b609-ec7212301948",
+            ItemType::Unknown(id) => id,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ItemType::SkinLevel => "skin level",
+            ItemType::SkinChroma => "skin chroma",
+            ItemType::Buddy => "buddy",
+            ItemType::Spray => "spray",
+            ItemType::Card => "card",
+            ItemType::Title => "title",
+            ItemType::Agent => "agent",
+            ItemType::Contract => "contract",
+            ItemType::Unknown(id) => id,
+        }
+    }
+}
+
+impl fmt::Display for ItemType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Serialize for ItemType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.id())
+    }
+}
+
+impl<'de> Deserialize<'de> for ItemType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        Ok(ItemType::from_id(&id))
+    }
+}
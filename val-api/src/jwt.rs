@@ -0,0 +1,147 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{
+    error::{Result, ValApiError},
+    region::Region,
+};
+
+/// Claims found in the access, id and entitlements tokens.
+///
+/// Only the fields riot-cli relies on are decoded, everything is optional
+/// since the three tokens carry different sets of claims.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Claims {
+    /// Puuid of the account.
+    pub sub: Option<String>,
+    /// Expiry as a unix timestamp in seconds.
+    pub exp: Option<i64>,
+    /// Issue time as a unix timestamp in seconds.
+    pub iat: Option<i64>,
+    /// Country of the account, only present in id tokens.
+    pub country: Option<String>,
+    /// Platform data of access tokens, `c` is the cluster (`eu`, `na`, ...).
+    #[serde(rename = "pp")]
+    pub platform: Option<PlatformClaims>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct PlatformClaims {
+    #[serde(rename = "c")]
+    pub cluster: Option<String>,
+}
+
+impl Claims {
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.exp.and_then(|exp| DateTime::from_timestamp(exp, 0))
+    }
+
+    pub fn issued_at(&self) -> Option<DateTime<Utc>> {
+        self.iat.and_then(|iat| DateTime::from_timestamp(iat, 0))
+    }
+
+    /// Region the token was issued for, the live affinity from
+    /// `get_region` should be preferred when available.
+    pub fn region_hint(&self) -> Option<Region> {
+        self.platform
+            .as_ref()
+            .and_then(|platform| platform.cluster.as_deref())
+            .and_then(|cluster| cluster.parse().ok())
+    }
+}
+
+/// Decodes the common claims of a token.
+pub fn decode_claims(token: &str) -> Result<Claims> {
+    decode_payload(token)
+}
+
+/// Decodes the payload of a JWT into `T`.
+///
+/// The signature is not verified, the tokens come straight from Riot and are
+/// only inspected to know when they have to be refreshed.
+pub fn decode_payload<T: DeserializeOwned>(token: &str) -> Result<T> {
+    let mut parts = token.split('.');
+    let (Some(_header), Some(payload), Some(_signature), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(ValApiError::InvalidToken(
+            "expected three dot separated parts".to_string(),
+        ));
+    };
+
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .map_err(|err| ValApiError::InvalidToken(format!("invalid base64 payload: {err}")))?;
+
+    serde_json::from_slice(&payload)
+        .map_err(|err| ValApiError::InvalidToken(format!("invalid payload: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(payload: &str) -> String {
+        format!(
+            "eyJhbGciOiJSUzI1NiJ9.{}.c2lnbmF0dXJl",
+            URL_SAFE_NO_PAD.encode(payload)
+        )
+    }
+
+    #[test]
+    fn decodes_access_token_claims() {
+        let claims = decode_claims(&token(
+            r#"{"sub":"puuid","exp":1700003600,"iat":1700000000,"pp":{"c":"eu"},"scp":["account"]}"#,
+        ))
+        .unwrap();
+
+        assert_eq!(claims.sub.as_deref(), Some("puuid"));
+        assert_eq!(
+            claims.expires_at(),
+            DateTime::from_timestamp(1_700_003_600, 0)
+        );
+        assert_eq!(
+            claims.issued_at(),
+            DateTime::from_timestamp(1_700_000_000, 0)
+        );
+        assert_eq!(claims.region_hint(), Some(Region::Eu));
+    }
+
+    #[test]
+    fn missing_claims_are_none() {
+        let claims = decode_claims(&token(r#"{"entitlements":[]}"#)).unwrap();
+
+        assert_eq!(claims.expires_at(), None);
+        assert_eq!(claims.region_hint(), None);
+    }
+
+    #[test]
+    fn unknown_cluster_has_no_region() {
+        let claims = decode_claims(&token(r#"{"pp":{"c":"moon"}}"#)).unwrap();
+
+        assert_eq!(claims.region_hint(), None);
+    }
+
+    #[test]
+    fn accepts_padded_payload() {
+        let payload = URL_SAFE_NO_PAD.encode(r#"{"exp":1}"#);
+        let claims = decode_claims(&format!("header.{payload}==.signature")).unwrap();
+
+        assert_eq!(claims.exp, Some(1));
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        for token in ["", "a.b", "a.b.c.d", "header.%%%.signature"] {
+            assert!(
+                matches!(decode_claims(token), Err(ValApiError::InvalidToken(_))),
+                "{token:?}"
+            );
+        }
+        assert!(matches!(
+            decode_claims(&token("not json")),
+            Err(ValApiError::InvalidToken(_))
+        ));
+    }
+}
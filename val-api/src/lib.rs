@@ -1,3 +1,14 @@
+pub mod client;
+pub mod currency;
 pub mod endpoints;
+pub mod error;
+pub mod item_type;
+pub mod jwt;
 pub mod models;
+pub mod rank;
+pub mod region;
+pub mod retry;
+pub mod season;
+#[cfg(test)]
+mod test_server;
 pub mod thirdparty;
@@ -1,6 +1,10 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::{currency::Currency, item_type::ItemType, jwt, region::Region, season::Season};
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AuthClientRequest {
     pub client_id: String,
@@ -29,11 +33,33 @@ pub struct LoginResponseData {
     pub parameters: LoginResponseParameters,
 }
 
+/// Answer of the authorization endpoint, which fields are set depends on
+/// `response_type` (`response`, `multifactor` or `auth` with an `error`).
 #[derive(Serialize, Deserialize, Debug)]
 pub struct LoginResponse {
     #[serde(rename = "type")]
     pub response_type: String,
-    pub response: LoginResponseData,
+    pub response: Option<LoginResponseData>,
+    pub multifactor: Option<MultifactorDetails>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MultifactorDetails {
+    /// Masked address the code was sent to, e.g. `jo***@gmail.com`.
+    pub email: Option<String>,
+    pub method: Option<String>,
+    pub multi_factor_code_length: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MultifactorRequest {
+    #[serde(rename = "type")]
+    pub action_type: String,
+    pub code: String,
+    pub remember_device: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -43,6 +69,17 @@ pub struct Tokens {
     pub expires_in: u64,
 }
 
+impl Tokens {
+    /// Expiry of the access token, read from its `exp` claim and falling back
+    /// to `expires_in` when the token cannot be decoded.
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        jwt::decode_claims(&self.access_token)
+            .ok()
+            .and_then(|claims| claims.expires_at())
+            .unwrap_or_else(|| Utc::now() + chrono::Duration::seconds(self.expires_in as i64))
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AuthResult {
     pub tokens: Tokens,
@@ -115,8 +152,8 @@ pub struct RegionRequest {
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Affinities {
-    pub pbe: String,
-    pub live: String,
+    pub pbe: Region,
+    pub live: Region,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -150,6 +187,23 @@ pub struct SkinLevel {
     pub streamed_video: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetDetails {
+    pub uuid: String,
+    pub display_name: Option<String>,
+    pub display_icon: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BuddyDetails {
+    pub uuid: String,
+    pub display_name: Option<String>,
+    pub display_icon: Option<String>,
+    pub levels: Vec<AssetDetails>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "PascalCase")]
 pub struct SkinPanelLayout {
@@ -161,8 +215,100 @@ pub struct SkinPanelLayout {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "PascalCase")]
 pub struct StoreFrontResponse {
+    pub featured_bundle: Option<FeaturedBundle>,
     pub skins_panel_layout: SkinPanelLayout,
     pub bonus_store: Option<BonusStore>,
+    pub accessory_store: Option<AccessoryStore>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct FeaturedBundle {
+    pub bundle: Bundle,
+    #[serde(default)]
+    pub bundles: Vec<Bundle>,
+    pub bundle_remaining_duration_in_seconds: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct Bundle {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "DataAssetID")]
+    pub data_asset_id: String,
+    #[serde(rename = "CurrencyID")]
+    pub currency_id: Currency,
+    pub items: Vec<BundleItem>,
+    pub total_base_cost: Option<PriceDetail>,
+    pub total_discounted_cost: Option<PriceDetail>,
+    pub total_discount_percent: Option<f64>,
+    pub duration_remaining_in_seconds: i64,
+    pub wholesale_only: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct BundleItem {
+    pub item: BundleItemReward,
+    pub base_price: u64,
+    #[serde(rename = "CurrencyID")]
+    pub currency_id: Currency,
+    pub discount_percent: f64,
+    pub discounted_price: u64,
+    pub is_promo_item: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct BundleItemReward {
+    #[serde(rename = "ItemTypeID")]
+    pub item_type_id: ItemType,
+    #[serde(rename = "ItemID")]
+    pub item_id: String,
+    pub amount: u64,
+}
+
+impl Bundle {
+    /// Full price of the bundle, summed from the items when the totals are missing.
+    pub fn base_cost(&self) -> u64 {
+        match self
+            .total_base_cost
+            .as_ref()
+            .and_then(|cost| cost.get(&self.currency_id))
+        {
+            Some(cost) => cost,
+            None => self.items.iter().map(|item| item.base_price).sum(),
+        }
+    }
+
+    pub fn discounted_cost(&self) -> u64 {
+        match self
+            .total_discounted_cost
+            .as_ref()
+            .and_then(|cost| cost.get(&self.currency_id))
+        {
+            Some(cost) => cost,
+            None => self.items.iter().map(|item| item.discounted_price).sum(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct AccessoryStore {
+    pub accessory_store_offers: Vec<AccessoryStoreOffer>,
+    pub accessory_store_remaining_duration_in_seconds: i64,
+    #[serde(rename = "StorefrontID")]
+    pub storefront_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct AccessoryStoreOffer {
+    pub offer: CatalogOffer,
+    #[serde(rename = "ContractID")]
+    pub contract_id: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -195,17 +341,405 @@ pub struct Offer {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct OffersResponse {
+    pub offers: Vec<CatalogOffer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct CatalogOffer {
+    #[serde(rename = "OfferID")]
+    pub offer_id: String,
+    pub is_direct_purchase: bool,
+    pub start_date: DateTime<Utc>,
+    pub cost: PriceDetail,
+    pub rewards: Vec<OfferReward>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct OfferReward {
+    #[serde(rename = "ItemTypeID")]
+    pub item_type_id: ItemType,
+    #[serde(rename = "ItemID")]
+    pub item_id: String,
+    pub quantity: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct EntitlementsResponse {
+    #[serde(rename = "ItemTypeID")]
+    pub item_type_id: ItemType,
+    pub entitlements: Vec<Entitlement>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct Entitlement {
+    #[serde(rename = "TypeID")]
+    pub type_id: Option<String>,
+    #[serde(rename = "ItemID")]
+    pub item_id: String,
+    #[serde(rename = "InstanceID")]
+    pub instance_id: Option<String>,
+}
+
+/// Every purchasable offer, indexed by offer and rewarded item.
+#[derive(Debug, Clone)]
+pub struct OffersCatalog {
+    offers: Vec<CatalogOffer>,
+    by_offer: HashMap<String, usize>,
+    by_item: HashMap<String, usize>,
+}
+
+impl From<OffersResponse> for OffersCatalog {
+    fn from(response: OffersResponse) -> Self {
+        let mut by_offer = HashMap::new();
+        let mut by_item = HashMap::new();
+        for (index, offer) in response.offers.iter().enumerate() {
+            by_offer.insert(offer.offer_id.clone(), index);
+            for reward in &offer.rewards {
+                by_item.entry(reward.item_id.clone()).or_insert(index);
+            }
+        }
+
+        Self {
+            offers: response.offers,
+            by_offer,
+            by_item,
+        }
+    }
+}
+
+impl OffersCatalog {
+    pub fn offers(&self) -> &[CatalogOffer] {
+        &self.offers
+    }
+
+    pub fn get(&self, offer_id: &str) -> Option<&CatalogOffer> {
+        self.by_offer
+            .get(offer_id)
+            .map(|index| &self.offers[*index])
+    }
+
+    /// Offer that rewards the item, skin levels are sold by their level uuid.
+    pub fn find_by_item(&self, item_id: &str) -> Option<&CatalogOffer> {
+        self.by_item.get(item_id).map(|index| &self.offers[*index])
+    }
+}
+
+/// Amount charged per currency, unknown currencies included.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(transparent)]
 pub struct PriceDetail {
-    #[serde(rename = "85ad13f7-3d1b-5128-9eb2-7cd8ee0b5741")]
-    pub valorant_points: u64,
-    // #[serde(skip_serializing)]
-    // pub radianite: u64,
+    pub amounts: HashMap<Currency, u64>,
+}
+
+impl PriceDetail {
+    pub fn get(&self, currency: &Currency) -> Option<u64> {
+        self.amounts.get(currency).copied()
+    }
+
+    pub fn valorant_points(&self) -> Option<u64> {
+        self.get(&Currency::ValorantPoints)
+    }
+
+    /// Amounts sorted by currency, so output is stable.
+    pub fn iter(&self) -> impl Iterator<Item = (&Currency, u64)> {
+        let mut amounts: Vec<_> = self.amounts.iter().map(|(c, a)| (c, *a)).collect();
+        amounts.sort_by(|a, b| a.0.cmp(b.0));
+        amounts.into_iter()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct Wallet {
+    pub balances: HashMap<Currency, u64>,
+}
+
+impl Wallet {
+    pub fn balance(&self, currency: &Currency) -> u64 {
+        self.balances.get(currency).copied().unwrap_or_default()
+    }
+
+    pub fn can_afford(&self, cost: &PriceDetail) -> bool {
+        cost.amounts
+            .iter()
+            .all(|(currency, amount)| self.balance(currency) >= *amount)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct MatchHistory {
+    pub subject: String,
+    pub begin_index: u32,
+    pub end_index: u32,
+    pub total: u32,
+    pub history: Vec<MatchHistoryEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct MatchHistoryEntry {
+    #[serde(rename = "MatchID")]
+    pub match_id: String,
+    pub game_start_time: i64,
+    #[serde(rename = "QueueID")]
+    pub queue_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchDetails {
+    pub match_info: MatchInfo,
+    pub players: Vec<MatchPlayer>,
+    #[serde(default)]
+    pub teams: Option<Vec<MatchTeam>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchInfo {
+    pub match_id: String,
+    pub map_id: String,
+    pub game_length_millis: Option<i64>,
+    pub game_start_millis: i64,
+    pub is_completed: bool,
+    #[serde(rename = "queueID")]
+    pub queue_id: String,
+    pub is_ranked: bool,
+    pub season_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchPlayer {
+    pub subject: String,
+    pub game_name: Option<String>,
+    pub tag_line: Option<String>,
+    pub team_id: String,
+    pub party_id: String,
+    pub character_id: Option<String>,
+    pub stats: Option<MatchPlayerStats>,
+    pub competitive_tier: u32,
+    pub account_level: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchPlayerStats {
+    pub score: i64,
+    pub rounds_played: u32,
+    pub kills: u32,
+    pub deaths: u32,
+    pub assists: u32,
+    pub playtime_millis: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchTeam {
+    pub team_id: String,
+    pub won: bool,
+    pub rounds_played: u32,
+    pub rounds_won: u32,
+    pub num_points: u32,
+}
+
+impl MatchDetails {
+    pub fn player(&self, puuid: &str) -> Option<&MatchPlayer> {
+        self.players.iter().find(|player| player.subject == puuid)
+    }
+
+    pub fn team(&self, team_id: &str) -> Option<&MatchTeam> {
+        self.teams
+            .as_ref()?
+            .iter()
+            .find(|team| team.team_id == team_id)
+    }
+
+    /// Rounds won by the team against the best opposing team.
+    pub fn score(&self, team_id: &str) -> Option<(u32, u32)> {
+        let team = self.team(team_id)?;
+        let opponent = self
+            .teams
+            .as_ref()?
+            .iter()
+            .filter(|other| other.team_id != team_id)
+            .map(|other| other.rounds_won)
+            .max()
+            .unwrap_or_default();
+
+        Some((team.rounds_won, opponent))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlayerMmr {
+    pub subject: String,
+    #[serde(default)]
+    pub queue_skills: HashMap<String, QueueSkill>,
+    pub latest_competitive_update: Option<CompetitiveUpdate>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct QueueSkill {
+    pub total_games_needed_for_rating: u32,
+    #[serde(rename = "SeasonalInfoBySeasonID")]
+    pub seasonal_info_by_season_id: Option<HashMap<String, SeasonalInfo>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct SeasonalInfo {
+    #[serde(rename = "SeasonID")]
+    pub season_id: String,
+    pub number_of_wins: u32,
+    pub number_of_games: u32,
+    pub rank: u32,
+    pub competitive_tier: u32,
+    pub ranked_rating: u32,
+    pub leaderboard_rank: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct CompetitiveUpdates {
+    pub subject: String,
+    pub matches: Vec<CompetitiveUpdate>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct CompetitiveUpdate {
+    #[serde(rename = "MatchID")]
+    pub match_id: String,
+    #[serde(rename = "MapID")]
+    pub map_id: String,
+    #[serde(rename = "SeasonID")]
+    pub season_id: String,
+    pub match_start_time: i64,
+    pub tier_after_update: u32,
+    pub tier_before_update: u32,
+    pub ranked_rating_after_update: u32,
+    pub ranked_rating_before_update: u32,
+    pub ranked_rating_earned: i32,
+    #[serde(rename = "AFKPenalty", default)]
+    pub afk_penalty: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlayerLoadout {
+    pub subject: String,
+    pub version: u64,
+    pub guns: Vec<GunLoadout>,
+    pub sprays: Vec<SprayLoadout>,
+    pub identity: IdentityLoadout,
+    pub incognito: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct GunLoadout {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "SkinID")]
+    pub skin_id: String,
+    #[serde(rename = "SkinLevelID")]
+    pub skin_level_id: String,
+    #[serde(rename = "ChromaID")]
+    pub chroma_id: String,
+    #[serde(rename = "CharmID")]
+    pub charm_id: Option<String>,
+    #[serde(rename = "CharmLevelID")]
+    pub charm_level_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct SprayLoadout {
+    #[serde(rename = "EquipSlotID")]
+    pub equip_slot_id: String,
+    #[serde(rename = "SprayID")]
+    pub spray_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct IdentityLoadout {
+    #[serde(rename = "PlayerCardID")]
+    pub player_card_id: String,
+    #[serde(rename = "PlayerTitleID")]
+    pub player_title_id: String,
+    pub account_level: u32,
+    #[serde(rename = "PreferredLevelBorderID")]
+    pub preferred_level_border_id: Option<String>,
+    pub hide_account_level: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlayerName {
+    pub display_name: String,
+    pub subject: String,
+    pub game_name: String,
+    pub tag_line: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContentResponse {
+    pub seasons: Vec<Season>,
+    #[serde(default)]
+    pub events: Vec<ContentEvent>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContentEvent {
+    #[serde(rename = "ID")]
+    pub id: String,
+    pub name: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    #[serde(default)]
+    pub is_active: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SeasonDetails {
+    pub uuid: String,
+    pub display_name: Option<String>,
+    #[serde(rename = "type")]
+    pub season_type: Option<String>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub parent_uuid: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MapDetails {
+    pub uuid: String,
+    pub display_name: Option<String>,
+    pub map_url: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct EmbedFooter {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub icon_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub proxy_icon_url: Option<String>,
 }
 
@@ -0,0 +1,112 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// Region affinity of an account, as returned by the riot-geo service.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Region {
+    Na,
+    Latam,
+    Br,
+    Eu,
+    Ap,
+    Kr,
+    Pbe,
+}
+
+/// Game server cluster hosting the pd/glz/shared services of a region.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Shard {
+    Na,
+    Eu,
+    Ap,
+    Kr,
+    Pbe,
+}
+
+impl Region {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Region::Na => "na",
+            Region::Latam => "latam",
+            Region::Br => "br",
+            Region::Eu => "eu",
+            Region::Ap => "ap",
+            Region::Kr => "kr",
+            Region::Pbe => "pbe",
+        }
+    }
+
+    // latam and br have their own glz servers but share the na shard
+    pub fn shard(&self) -> Shard {
+        match self {
+            Region::Na | Region::Latam | Region::Br => Shard::Na,
+            Region::Eu => Shard::Eu,
+            Region::Ap => Shard::Ap,
+            Region::Kr => Shard::Kr,
+            Region::Pbe => Shard::Pbe,
+        }
+    }
+
+    /// Region used in the `glz-{region}-1` host.
+    pub fn glz_region(&self) -> &'static str {
+        match self {
+            Region::Pbe => "na",
+            region => region.as_str(),
+        }
+    }
+}
+
+impl Shard {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Shard::Na => "na",
+            Shard::Eu => "eu",
+            Shard::Ap => "ap",
+            Shard::Kr => "kr",
+            Shard::Pbe => "pbe",
+        }
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownRegion(pub String);
+
+impl fmt::Display for UnknownRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown region affinity {:?}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownRegion {}
+
+impl FromStr for Region {
+    type Err = UnknownRegion;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "na" => Ok(Region::Na),
+            "latam" => Ok(Region::Latam),
+            "br" => Ok(Region::Br),
+            "eu" => Ok(Region::Eu),
+            "ap" => Ok(Region::Ap),
+            "kr" => Ok(Region::Kr),
+            "pbe" => Ok(Region::Pbe),
+            _ => Err(UnknownRegion(s.to_string())),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use crate::error::ValApiError;
+
+/// How failed requests are retried by `ValClient`.
+///
+/// Rate limits and connection failures are always retried since the server
+/// never processed the request, other failures only for idempotent requests
+/// unless `retry_non_idempotent` is set.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one, `1` disables retries.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    /// Upper bound of the backoff, a `Retry-After` above this gives up.
+    pub max_delay: Duration,
+    pub jitter: bool,
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before the next attempt, `None` when the error should be returned.
+    pub fn retry_delay(
+        &self,
+        err: &ValApiError,
+        attempt: u32,
+        idempotent: bool,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        let retryable = match err {
+            ValApiError::RateLimited { retry_after } => {
+                return match retry_after {
+                    Some(retry_after) if *retry_after > self.max_delay => None,
+                    Some(retry_after) => Some(*retry_after),
+                    None => Some(self.backoff(attempt)),
+                };
+            }
+            ValApiError::Transport(err) if err.is_connect() => {
+                return Some(self.backoff(attempt));
+            }
+            ValApiError::Transport(err) => err.is_timeout() || err.is_request(),
+            ValApiError::Status { status, .. } => status.is_server_error(),
+            ValApiError::Decode { .. }
+            | ValApiError::AuthExpired
+            | ValApiError::InvalidToken(_)
+            | ValApiError::InvalidUrl { .. }
+            | ValApiError::AuthClient(_) => false,
+        };
+
+        (retryable && (idempotent || self.retry_non_idempotent)).then(|| self.backoff(attempt))
+    }
+
+    /// Exponential backoff with full jitter.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)));
+        let delay = exponential.min(self.max_delay);
+
+        if !self.jitter {
+            return delay;
+        }
+
+        let random = RandomState::new().build_hasher().finish();
+        delay.mul_f64((random % 1000) as f64 / 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::StatusCode;
+
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(4),
+            jitter: false,
+            retry_non_idempotent: false,
+        }
+    }
+
+    fn server_error() -> ValApiError {
+        ValApiError::Status {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_each_attempt() {
+        let policy = policy();
+        let delays: Vec<_> = (1..=4)
+            .map(|attempt| policy.retry_delay(&server_error(), attempt, true))
+            .collect();
+
+        assert_eq!(
+            delays,
+            [500, 1000, 2000, 4000].map(|ms| Some(Duration::from_millis(ms)))
+        );
+    }
+
+    #[test]
+    fn backoff_is_capped_at_max_delay() {
+        let policy = policy();
+
+        assert_eq!(
+            policy.retry_delay(&server_error(), 8, true),
+            Some(policy.max_delay)
+        );
+    }
+
+    #[test]
+    fn jitter_stays_below_backoff() {
+        let policy = RetryPolicy {
+            jitter: true,
+            ..policy()
+        };
+
+        for _ in 0..100 {
+            let delay = policy.retry_delay(&server_error(), 2, true).unwrap();
+            assert!(delay <= Duration::from_millis(1000));
+        }
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let policy = policy();
+
+        assert_eq!(policy.retry_delay(&server_error(), 10, true), None);
+    }
+
+    #[test]
+    fn retry_after_is_honored() {
+        let policy = policy();
+        let err = ValApiError::RateLimited {
+            retry_after: Some(Duration::from_secs(3)),
+        };
+
+        assert_eq!(
+            policy.retry_delay(&err, 1, false),
+            Some(Duration::from_secs(3))
+        );
+    }
+
+    #[test]
+    fn retry_after_above_max_delay_gives_up() {
+        let policy = policy();
+        let err = ValApiError::RateLimited {
+            retry_after: Some(Duration::from_secs(60)),
+        };
+
+        assert_eq!(policy.retry_delay(&err, 1, true), None);
+    }
+
+    #[test]
+    fn rate_limit_without_retry_after_backs_off() {
+        let policy = policy();
+        let err = ValApiError::RateLimited { retry_after: None };
+
+        assert_eq!(
+            policy.retry_delay(&err, 2, false),
+            Some(Duration::from_millis(1000))
+        );
+    }
+
+    #[test]
+    fn non_idempotent_requests_are_not_retried() {
+        let policy = policy();
+
+        assert_eq!(policy.retry_delay(&server_error(), 1, false), None);
+
+        let policy = RetryPolicy {
+            retry_non_idempotent: true,
+            ..policy
+        };
+        assert_eq!(
+            policy.retry_delay(&server_error(), 1, false),
+            Some(policy.base_delay)
+        );
+    }
+
+    #[test]
+    fn client_errors_are_not_retried() {
+        let policy = policy();
+        let err = ValApiError::Status {
+            status: StatusCode::BAD_REQUEST,
+            body: String::new(),
+        };
+
+        assert_eq!(policy.retry_delay(&err, 1, true), None);
+        assert_eq!(policy.retry_delay(&ValApiError::AuthExpired, 1, true), None);
+    }
+}
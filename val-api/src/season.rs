@@ -0,0 +1,146 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SeasonType {
+    Episode,
+    Act,
+    #[serde(other)]
+    Other,
+}
+
+/// Episode or act as listed by the content service.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct Season {
+    #[serde(rename = "ID")]
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "Type")]
+    pub season_type: SeasonType,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    #[serde(default)]
+    pub is_active: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Act {
+    pub id: String,
+    pub name: String,
+    pub episode_name: Option<String>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+}
+
+impl Season {
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.start_time <= time && time < self.end_time
+    }
+}
+
+impl Act {
+    /// Name including the episode, e.g. `EPISODE 9 ACT III`.
+    pub fn full_name(&self) -> String {
+        match &self.episode_name {
+            Some(episode) => format!("{episode} {}", self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Act running at `time` and the episode it belongs to.
+pub fn act_at(seasons: &[Season], time: DateTime<Utc>) -> Option<Act> {
+    let act = seasons
+        .iter()
+        .find(|season| season.season_type == SeasonType::Act && season.contains(time))?;
+    let episode = seasons.iter().find(|season| {
+        season.season_type == SeasonType::Episode && season.contains(act.start_time)
+    });
+
+    Some(Act {
+        id: act.id.clone(),
+        name: act.name.clone(),
+        episode_name: episode.map(|episode| episode.name.clone()),
+        start_time: act.start_time,
+        end_time: act.end_time,
+    })
+}
+
+pub fn current_act(seasons: &[Season]) -> Option<Act> {
+    act_at(seasons, Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn season(id: &str, season_type: SeasonType, start: u32, end: u32) -> Season {
+        Season {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            season_type,
+            start_time: Utc.with_ymd_and_hms(2024, start, 1, 0, 0, 0).unwrap(),
+            end_time: Utc.with_ymd_and_hms(2024, end, 1, 0, 0, 0).unwrap(),
+            is_active: false,
+        }
+    }
+
+    fn seasons() -> Vec<Season> {
+        vec![
+            season("episode 8", SeasonType::Episode, 1, 7),
+            season("act i", SeasonType::Act, 1, 3),
+            season("act ii", SeasonType::Act, 3, 5),
+            season("act iii", SeasonType::Act, 5, 7),
+            season("episode 9", SeasonType::Episode, 7, 12),
+            season("act i", SeasonType::Act, 7, 9),
+        ]
+    }
+
+    #[test]
+    fn act_at_picks_the_running_act_and_its_episode() {
+        let time = Utc.with_ymd_and_hms(2024, 4, 15, 0, 0, 0).unwrap();
+
+        let act = act_at(&seasons(), time).unwrap();
+
+        assert_eq!(act.full_name(), "EPISODE 8 ACT II");
+    }
+
+    #[test]
+    fn act_at_matches_the_episode_of_a_repeated_act_name() {
+        let time = Utc.with_ymd_and_hms(2024, 8, 1, 0, 0, 0).unwrap();
+
+        let act = act_at(&seasons(), time).unwrap();
+
+        assert_eq!(act.full_name(), "EPISODE 9 ACT I");
+    }
+
+    #[test]
+    fn act_at_switches_on_the_start_time() {
+        let time = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+
+        let act = act_at(&seasons(), time).unwrap();
+
+        assert_eq!(act.full_name(), "EPISODE 8 ACT III");
+    }
+
+    #[test]
+    fn act_at_is_none_between_acts() {
+        let time = Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap();
+
+        assert!(act_at(&seasons(), time).is_none());
+    }
+
+    #[test]
+    fn act_at_works_without_the_episode() {
+        let seasons = [season("act i", SeasonType::Act, 1, 3)];
+        let time = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+
+        let act = act_at(&seasons, time).unwrap();
+
+        assert_eq!(act.full_name(), "ACT I");
+    }
+}
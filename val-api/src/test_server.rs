@@ -0,0 +1,53 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+/// Serves the canned responses in order, one connection per request.
+pub async fn serve(responses: &'static [&'static str]) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+
+    let counter = requests.clone();
+    tokio::spawn(async move {
+        for response in responses {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            read_request(&mut stream).await;
+            counter.fetch_add(1, Ordering::SeqCst);
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+
+    (base, requests)
+}
+
+/// Reads the headers and the body announced by `Content-Length`.
+async fn read_request(stream: &mut TcpStream) {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    loop {
+        if let Some(end) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+            let headers = String::from_utf8_lossy(&request[..end]).to_lowercase();
+            let length = headers
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .and_then(|length| length.trim().parse::<usize>().ok())
+                .unwrap_or_default();
+            if request.len() >= end + 4 + length {
+                return;
+            }
+        }
+
+        let read = stream.read(&mut buf).await.unwrap();
+        if read == 0 {
+            return;
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+}
@@ -1,6 +1,7 @@
-use crate::models::WebhookMessage;
+use crate::{client::ValClient, error::Result, models::WebhookMessage};
 
-pub async fn send_webhook(url: &str, message: &WebhookMessage) {
-    let client = reqwest::Client::new();
-    client.post(url).json(&message).send().await.unwrap();
+pub async fn send_webhook(client: &ValClient, url: &str, message: &WebhookMessage) -> Result<()> {
+    client.send(client.http().post(url).json(&message)).await?;
+
+    Ok(())
 }
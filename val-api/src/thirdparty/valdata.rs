@@ -1,37 +1,92 @@
-use crate::models::{SkinDetails, UnofficalApiResponse, ValorantVersionResponse};
+use chrono::Utc;
+use serde::de::DeserializeOwned;
 
-pub async fn get_valorant_version() -> ValorantVersionResponse {
-    const ENDPOINT: &str = "https://valorant-api.com/v1/version";
-    let client = reqwest::Client::new();
+use crate::{
+    client::ValClient,
+    error::{self, Result},
+    models::{
+        AssetDetails, BuddyDetails, MapDetails, SeasonDetails, SkinDetails, UnofficalApiResponse,
+        ValorantVersionResponse,
+    },
+    season::{Season, SeasonType},
+};
 
-    let res = client.get(ENDPOINT).send().await.unwrap();
-    let res = res
-        .json::<UnofficalApiResponse<ValorantVersionResponse>>()
-        .await
-        .unwrap();
+async fn get_data<T: DeserializeOwned>(client: &ValClient, path: &str) -> Result<T> {
+    let endpoint = format!("{}{path}", client.urls().valorant_api);
 
-    return res.data;
+    let res = client.send(client.http().get(endpoint)).await?;
+    let res = error::json::<UnofficalApiResponse<T>>(res).await?;
+
+    Ok(res.data)
+}
+
+pub async fn get_valorant_version(client: &ValClient) -> Result<ValorantVersionResponse> {
+    get_data(client, "/v1/version").await
 }
 
-pub async fn get_client_version() -> String {
-    let version = get_valorant_version().await;
-    format!(
+pub async fn get_client_version(client: &ValClient) -> Result<String> {
+    let version = get_valorant_version(client).await?;
+    Ok(format!(
         "{}-shipping-{}-{}",
         version.branch,
         version.build_version,
-        version.version.split('.').nth(3).unwrap()
-    )
+        version.version.rsplit('.').next().unwrap_or_default()
+    ))
+}
+
+pub async fn get_weapon_skins(client: &ValClient) -> Result<Vec<SkinDetails>> {
+    get_data(client, "/v1/weapons/skins?language=en-US").await
+}
+
+pub async fn get_bundles(client: &ValClient) -> Result<Vec<AssetDetails>> {
+    get_data(client, "/v1/bundles?language=en-US").await
+}
+
+pub async fn get_sprays(client: &ValClient) -> Result<Vec<AssetDetails>> {
+    get_data(client, "/v1/sprays?language=en-US").await
+}
+
+pub async fn get_player_cards(client: &ValClient) -> Result<Vec<AssetDetails>> {
+    get_data(client, "/v1/playercards?language=en-US").await
+}
+
+pub async fn get_player_titles(client: &ValClient) -> Result<Vec<AssetDetails>> {
+    get_data(client, "/v1/playertitles?language=en-US").await
+}
+
+pub async fn get_buddies(client: &ValClient) -> Result<Vec<BuddyDetails>> {
+    get_data(client, "/v1/buddies?language=en-US").await
 }
 
-pub async fn get_weapon_skins() -> Vec<SkinDetails> {
-    const ENDPOINT: &str = "https://valorant-api.com/v1/weapons/skins?language=en-US";
-    let client = reqwest::Client::new();
+pub async fn get_agents(client: &ValClient) -> Result<Vec<AssetDetails>> {
+    get_data(client, "/v1/agents?isPlayableCharacter=true&language=en-US").await
+}
+
+pub async fn get_maps(client: &ValClient) -> Result<Vec<MapDetails>> {
+    get_data(client, "/v1/maps?language=en-US").await
+}
+
+pub async fn get_weapons(client: &ValClient) -> Result<Vec<AssetDetails>> {
+    get_data(client, "/v1/weapons?language=en-US").await
+}
 
-    let res = client.get(ENDPOINT).send().await.unwrap();
-    let res = res
-        .json::<UnofficalApiResponse<Vec<SkinDetails>>>()
-        .await
-        .unwrap();
+/// Seasons in the same shape as the content service, acts have a season type.
+pub async fn get_seasons(client: &ValClient) -> Result<Vec<Season>> {
+    let seasons: Vec<SeasonDetails> = get_data(client, "/v1/seasons?language=en-US").await?;
 
-    res.data
+    Ok(seasons
+        .into_iter()
+        .map(|season| Season {
+            is_active: season.start_time <= Utc::now() && Utc::now() < season.end_time,
+            id: season.uuid,
+            name: season.display_name.unwrap_or_default(),
+            season_type: match season.season_type.as_deref() {
+                Some(kind) if kind.ends_with("Act") => SeasonType::Act,
+                Some(_) => SeasonType::Other,
+                None => SeasonType::Episode,
+            },
+            start_time: season.start_time,
+            end_time: season.end_time,
+        })
+        .collect())
 }
@@ -1,3 +1,6 @@
+//! WebView2 login window, the crate is empty on other platforms.
+#![cfg(windows)]
+
 use std::{
     cell::{Cell, OnceCell},
     path::Path,
@@ -19,14 +22,7 @@ use winapi::shared::{
     winerror::E_FAIL,
 };
 
-pub const RIOT_AUTH_PAGE: &str = concat!(
-    "https://auth.riotgames.com/authorize?",
-    "redirect_uri=https%3A%2F%2Fplayvalorant.com%2Fopt_in&",
-    "client_id=play-valorant-web-prod&",
-    "response_type=token%20id_token&",
-    "scope=account%20openid&",
-    "nonce=1"
-);
+pub use val_api::endpoints::auth::RIOT_AUTH_PAGE;
 
 pub fn login_popup(profile_folder: &Path, login_page: &str) -> Option<(Tokens, String)> {
     let mut event_loop = EventLoop::new();